@@ -0,0 +1,6 @@
+-- Add retry settings to monitors
+ALTER TABLE monitors ADD COLUMN max_retries INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE monitors ADD COLUMN retry_interval INTEGER NOT NULL DEFAULT 60;
+
+-- Number of consecutive failed checks
+ALTER TABLE monitors ADD COLUMN retries INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Type-specific monitor settings
+ALTER TABLE monitors ADD COLUMN config TEXT NOT NULL DEFAULT '{}'; -- JSON string
+
+-- Type-specific check details
+ALTER TABLE monitor_status_history ADD COLUMN details TEXT; -- JSON string
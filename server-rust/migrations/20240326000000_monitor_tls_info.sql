@@ -0,0 +1,26 @@
+-- Create monitor_tls_info table
+CREATE TABLE IF NOT EXISTS monitor_tls_info (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    monitor_id INTEGER NOT NULL UNIQUE,
+    info_json TEXT NOT NULL, -- JSON string
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (monitor_id) REFERENCES monitors(id) ON DELETE CASCADE
+);
+
+-- Create notification_sent_history table to avoid repeating notifications
+CREATE TABLE IF NOT EXISTS notification_sent_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    type TEXT NOT NULL,
+    monitor_id INTEGER NOT NULL,
+    days INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (type, monitor_id, days),
+    FOREIGN KEY (monitor_id) REFERENCES monitors(id) ON DELETE CASCADE
+);
+
+CREATE TRIGGER IF NOT EXISTS update_monitor_tls_info_updated_at
+    AFTER UPDATE ON monitor_tls_info
+BEGIN
+    UPDATE monitor_tls_info SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
@@ -0,0 +1,4 @@
+-- Add push token for passive (push) monitors
+ALTER TABLE monitors ADD COLUMN push_token TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_monitors_push_token ON monitors(push_token);
@@ -0,0 +1,7 @@
+-- Nest monitors under group monitors
+ALTER TABLE monitors ADD COLUMN parent INTEGER REFERENCES monitors(id) ON DELETE CASCADE;
+
+-- Paused monitors are not checked
+ALTER TABLE monitors ADD COLUMN active BOOLEAN NOT NULL DEFAULT 1;
+
+CREATE INDEX IF NOT EXISTS idx_monitors_parent ON monitors(parent);
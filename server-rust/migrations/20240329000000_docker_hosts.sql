@@ -0,0 +1,19 @@
+-- Create docker_hosts table
+CREATE TABLE IF NOT EXISTS docker_hosts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    docker_type TEXT NOT NULL, -- "socket" or "tcp"
+    docker_daemon TEXT NOT NULL, -- Socket path or TCP URL
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_docker_hosts_user_id ON docker_hosts(user_id);
+
+CREATE TRIGGER IF NOT EXISTS update_docker_hosts_updated_at
+    AFTER UPDATE ON docker_hosts
+BEGIN
+    UPDATE docker_hosts SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
@@ -0,0 +1,2 @@
+-- Conditions evaluated against the variables of each check
+ALTER TABLE monitors ADD COLUMN conditions TEXT NOT NULL DEFAULT '[]';
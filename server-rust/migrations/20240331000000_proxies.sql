@@ -0,0 +1,26 @@
+-- Create proxies table
+CREATE TABLE IF NOT EXISTS proxies (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    protocol TEXT NOT NULL, -- "http", "https", "socks5" or "socks5h"
+    host TEXT NOT NULL,
+    port INTEGER NOT NULL,
+    auth BOOLEAN NOT NULL DEFAULT 0,
+    username TEXT,
+    password TEXT,
+    is_default BOOLEAN NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_proxies_user_id ON proxies(user_id);
+
+CREATE TRIGGER IF NOT EXISTS update_proxies_updated_at
+    AFTER UPDATE ON proxies
+BEGIN
+    UPDATE proxies SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
+
+-- Proxy used by HTTP based monitors
+ALTER TABLE monitors ADD COLUMN proxy_id INTEGER REFERENCES proxies(id) ON DELETE SET NULL;
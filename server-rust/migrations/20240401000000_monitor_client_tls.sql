@@ -0,0 +1,4 @@
+-- Client certificate for mutual TLS and custom CA bundle, as PEM
+ALTER TABLE monitors ADD COLUMN tls_cert TEXT;
+ALTER TABLE monitors ADD COLUMN tls_key TEXT;
+ALTER TABLE monitors ADD COLUMN tls_ca TEXT;
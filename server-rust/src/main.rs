@@ -15,17 +15,37 @@ mod services;
 mod error;
 mod config;
 mod middleware;
+mod monitor_types;
+mod monitor_conditions;
 
 use config::database::{init_db, close_db};
-use routes::{health::health_check, auth::auth_routes, monitor::monitor_routes};
-use services::{auth::AuthService, monitor::MonitorService};
+use routes::{
+    health::health_check,
+    auth::auth_routes,
+    monitor::monitor_routes,
+    push::push_routes,
+    docker_host::docker_host_routes,
+    proxy::proxy_routes,
+};
+use services::{
+    auth::AuthService,
+    docker_host::DockerHostService,
+    proxy::ProxyService,
+    monitor::MonitorService,
+    notification::NotificationService,
+    scheduler::Scheduler,
+};
 use middleware::auth::auth_middleware;
+use monitor_types::{http::HttpClients, tls::TlsInspector};
 
 #[derive(Clone)]
 struct AppState {
     db: SqlitePool,
     auth_service: Arc<AuthService>,
     monitor_service: Arc<MonitorService>,
+    scheduler: Arc<Scheduler>,
+    docker_host_service: Arc<DockerHostService>,
+    proxy_service: Arc<ProxyService>,
 }
 
 #[tokio::main]
@@ -39,19 +59,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize database
     let db = init_db().await?;
     let auth_service = Arc::new(AuthService::new(db.clone()));
-    let monitor_service = Arc::new(MonitorService::new(db.clone()));
+    let notification_service = Arc::new(NotificationService::new(db.clone()));
+    let tls_inspector = Arc::new(TlsInspector::new(db.clone(), notification_service.clone()));
+    let http_clients = Arc::new(HttpClients::new(db.clone(), tls_inspector));
+    let monitor_service = Arc::new(MonitorService::new(db.clone(), notification_service.clone(), http_clients.clone()));
+    let scheduler = Arc::new(Scheduler::new(db.clone(), monitor_service.clone()));
+    let docker_host_service = Arc::new(DockerHostService::new(db.clone()));
+    let proxy_service = Arc::new(ProxyService::new(db.clone(), http_clients));
     let state = Arc::new(AppState {
         db: db.clone(),
         auth_service: auth_service.clone(),
         monitor_service: monitor_service.clone(),
+        scheduler: scheduler.clone(),
+        docker_host_service,
+        proxy_service,
     });
 
+    // Start background monitor checks
+    if let Err(e) = scheduler.start().await {
+        tracing::error!("Failed to start monitor scheduler: {:?}", e);
+    }
+
     // Create router
     let app = Router::new()
         .route("/", get(health_check))
         .route("/api/status", get(health_check))
         .nest("/api/auth", auth_routes())
         .nest("/api/monitors", monitor_routes())
+        .nest("/api/push", push_routes())
+        .nest("/api/docker-hosts", docker_host_routes())
+        .nest("/api/proxies", proxy_routes())
         .layer(CorsLayer::permissive())
         .layer(middleware::from_fn(auth_middleware))
         .with_state(state.clone());
@@ -63,6 +100,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Run the server
     axum::serve(listener, app).await?;
 
+    // Stop background monitor checks
+    scheduler.stop();
+
     // Close database connection
     close_db(&db).await?;
 
@@ -17,6 +17,7 @@ pub async fn auth_middleware<B>(
 ) -> Result<Response, StatusCode> {
     // Skip auth for public routes
     if req.uri().path().starts_with("/api/auth") ||
+       req.uri().path().starts_with("/api/push") ||
        req.uri().path() == "/" ||
        req.uri().path() == "/api/status" {
         return Ok(next.run(req).await);
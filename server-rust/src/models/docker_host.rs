@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+pub const DOCKER_TYPE_SOCKET: &str = "socket";
+pub const DOCKER_TYPE_TCP: &str = "tcp";
+
+/// A Docker Engine the "docker" monitor type can query.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct DockerHost {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    /// "socket" or "tcp".
+    pub docker_type: String,
+    /// Socket path, e.g. `/var/run/docker.sock`, or URL, e.g. `tcp://host:2375`.
+    pub docker_daemon: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDockerHost {
+    pub name: String,
+    pub docker_type: String,
+    pub docker_daemon: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDockerHost {
+    pub name: Option<String>,
+    pub docker_type: Option<String>,
+    pub docker_daemon: Option<String>,
+}
+
+impl DockerHost {
+    pub async fn create(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+        host: CreateDockerHost,
+    ) -> Result<Self, sqlx::Error> {
+        let result = sqlx::query_as!(
+            DockerHost,
+            r#"
+            INSERT INTO docker_hosts (user_id, name, docker_type, docker_daemon)
+            VALUES (?, ?, ?, ?)
+            RETURNING id, user_id, name, docker_type, docker_daemon, created_at, updated_at
+            "#,
+            user_id,
+            host.name,
+            host.docker_type,
+            host.docker_daemon
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn find_by_id(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        user_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            DockerHost,
+            r#"
+            SELECT id, user_id, name, docker_type, docker_daemon, created_at, updated_at
+            FROM docker_hosts
+            WHERE id = ? AND user_id = ?
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn list_by_user(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            DockerHost,
+            r#"
+            SELECT id, user_id, name, docker_type, docker_daemon, created_at, updated_at
+            FROM docker_hosts
+            WHERE user_id = ?
+            ORDER BY name
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn update(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        user_id: i64,
+        host: UpdateDockerHost,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            DockerHost,
+            r#"
+            UPDATE docker_hosts
+            SET
+                name = COALESCE(?, name),
+                docker_type = COALESCE(?, docker_type),
+                docker_daemon = COALESCE(?, docker_daemon)
+            WHERE id = ? AND user_id = ?
+            RETURNING id, user_id, name, docker_type, docker_daemon, created_at, updated_at
+            "#,
+            host.name,
+            host.docker_type,
+            host.docker_daemon,
+            id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn delete(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        user_id: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM docker_hosts
+            WHERE id = ? AND user_id = ?
+            "#,
+            id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Number of the user's docker monitors that run on this host.
+    pub async fn monitor_count(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        user_id: i64,
+    ) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!: i64"
+            FROM monitors
+            WHERE user_id = ? AND type = 'docker' AND json_extract(config, '$.docker_host') = ?
+            "#,
+            user_id,
+            id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result.count)
+    }
+}
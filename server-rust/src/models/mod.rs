@@ -3,6 +3,10 @@ use chrono::{DateTime, Utc};
 
 pub mod user;
 pub mod monitor;
+pub mod notification;
+pub mod docker_host;
+pub mod proxy;
+pub mod tls_info;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct User {
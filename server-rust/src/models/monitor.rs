@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, types::Json};
 use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use crate::monitor_conditions::expression::Condition;
+
+pub const STATUS_UP: &str = "up";
+pub const STATUS_DOWN: &str = "down";
+pub const STATUS_PENDING: &str = "pending";
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Monitor {
@@ -12,6 +19,25 @@ pub struct Monitor {
     pub type_: String,
     pub interval: i32,
     pub timeout: i32,
+    pub max_retries: i32,
+    pub retry_interval: i32,
+    pub retries: i32,
+    pub config: Json<Value>,
+    pub conditions: Json<Vec<Condition>>,
+    pub push_token: Option<String>,
+    /// Group monitor this monitor belongs to.
+    pub parent: Option<i64>,
+    pub active: bool,
+    /// Proxy used by HTTP based monitor types.
+    pub proxy_id: Option<i64>,
+    /// Client certificate presented by HTTP based monitor types, as PEM.
+    pub tls_cert: Option<String>,
+    /// Private key of the client certificate, encrypted with `SECRET_KEY`
+    /// (see `config::secret`) and never sent back to clients.
+    #[serde(skip_serializing)]
+    pub tls_key: Option<String>,
+    /// CA bundle trusted in addition to the WebPKI roots, as PEM.
+    pub tls_ca: Option<String>,
     pub status: String,
     pub last_check: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -26,6 +52,16 @@ pub struct CreateMonitor {
     pub type_: String,
     pub interval: Option<i32>,
     pub timeout: Option<i32>,
+    pub max_retries: Option<i32>,
+    pub retry_interval: Option<i32>,
+    pub config: Option<Value>,
+    pub conditions: Option<Vec<Condition>>,
+    pub push_token: Option<String>,
+    pub parent: Option<i64>,
+    pub proxy_id: Option<i64>,
+    pub tls_cert: Option<String>,
+    pub tls_key: Option<String>,
+    pub tls_ca: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -36,6 +72,41 @@ pub struct UpdateMonitor {
     pub type_: Option<String>,
     pub interval: Option<i32>,
     pub timeout: Option<i32>,
+    pub max_retries: Option<i32>,
+    pub retry_interval: Option<i32>,
+    pub config: Option<Value>,
+    pub conditions: Option<Vec<Condition>>,
+    pub push_token: Option<String>,
+    /// `null` moves the monitor out of its group, a missing field keeps it.
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub parent: Option<Option<i64>>,
+    /// `null` stops using a proxy, a missing field keeps it.
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub proxy_id: Option<Option<i64>>,
+    /// `null` removes the client certificate, a missing field keeps it.
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub tls_cert: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub tls_key: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub tls_ca: Option<Option<String>>,
+}
+
+/// Lets `UpdateMonitor` tell an explicit `null` apart from a missing field.
+fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// A monitor together with the monitors nested under it.
+#[derive(Debug, Serialize)]
+pub struct MonitorNode {
+    #[serde(flatten)]
+    pub monitor: Monitor,
+    pub children: Vec<MonitorNode>,
 }
 
 impl Monitor {
@@ -48,17 +119,27 @@ impl Monitor {
             Monitor,
             r#"
             INSERT INTO monitors (
-                user_id, name, url, type, interval, timeout, status
+                user_id, name, url, type, interval, timeout, max_retries, retry_interval, config, conditions, push_token, parent, proxy_id, tls_cert, tls_key, tls_ca, status
             )
-            VALUES (?, ?, ?, ?, ?, ?, 'unknown')
-            RETURNING id, user_id, name, url, type, interval, timeout, status, last_check, created_at, updated_at
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'unknown')
+            RETURNING id, user_id, name, url, type, interval, timeout, max_retries, retry_interval, retries, config as "config: Json<Value>", conditions as "conditions: Json<Vec<Condition>>", push_token, parent, active, proxy_id, tls_cert, tls_key, tls_ca, status, last_check, created_at, updated_at
             "#,
             user_id,
             monitor.name,
             monitor.url,
             monitor.type_,
             monitor.interval.unwrap_or(60),
-            monitor.timeout.unwrap_or(30)
+            monitor.timeout.unwrap_or(30),
+            monitor.max_retries.unwrap_or(0),
+            monitor.retry_interval.or(monitor.interval).unwrap_or(60),
+            Json(monitor.config.unwrap_or_else(|| Value::Object(Default::default()))),
+            Json(monitor.conditions.unwrap_or_default()),
+            monitor.push_token,
+            monitor.parent,
+            monitor.proxy_id,
+            monitor.tls_cert,
+            monitor.tls_key,
+            monitor.tls_ca
         )
         .fetch_one(pool)
         .await?;
@@ -74,7 +155,7 @@ impl Monitor {
         let result = sqlx::query_as!(
             Monitor,
             r#"
-            SELECT id, user_id, name, url, type, interval, timeout, status, last_check, created_at, updated_at
+            SELECT id, user_id, name, url, type, interval, timeout, max_retries, retry_interval, retries, config as "config: Json<Value>", conditions as "conditions: Json<Vec<Condition>>", push_token, parent, active, proxy_id, tls_cert, tls_key, tls_ca, status, last_check, created_at, updated_at
             FROM monitors
             WHERE id = ? AND user_id = ?
             "#,
@@ -94,7 +175,7 @@ impl Monitor {
         let result = sqlx::query_as!(
             Monitor,
             r#"
-            SELECT id, user_id, name, url, type, interval, timeout, status, last_check, created_at, updated_at
+            SELECT id, user_id, name, url, type, interval, timeout, max_retries, retry_interval, retries, config as "config: Json<Value>", conditions as "conditions: Json<Vec<Condition>>", push_token, parent, active, proxy_id, tls_cert, tls_key, tls_ca, status, last_check, created_at, updated_at
             FROM monitors
             WHERE user_id = ?
             ORDER BY created_at DESC
@@ -107,6 +188,61 @@ impl Monitor {
         Ok(result)
     }
 
+    pub async fn find(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Monitor,
+            r#"
+            SELECT id, user_id, name, url, type, interval, timeout, max_retries, retry_interval, retries, config as "config: Json<Value>", conditions as "conditions: Json<Vec<Condition>>", push_token, parent, active, proxy_id, tls_cert, tls_key, tls_ca, status, last_check, created_at, updated_at
+            FROM monitors
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn find_by_push_token(
+        pool: &sqlx::SqlitePool,
+        push_token: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Monitor,
+            r#"
+            SELECT id, user_id, name, url, type, interval, timeout, max_retries, retry_interval, retries, config as "config: Json<Value>", conditions as "conditions: Json<Vec<Condition>>", push_token, parent, active, proxy_id, tls_cert, tls_key, tls_ca, status, last_check, created_at, updated_at
+            FROM monitors
+            WHERE push_token = ?
+            "#,
+            push_token
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn list_all(
+        pool: &sqlx::SqlitePool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Monitor,
+            r#"
+            SELECT id, user_id, name, url, type, interval, timeout, max_retries, retry_interval, retries, config as "config: Json<Value>", conditions as "conditions: Json<Vec<Condition>>", push_token, parent, active, proxy_id, tls_cert, tls_key, tls_ca, status, last_check, created_at, updated_at
+            FROM monitors
+            ORDER BY id
+            "#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(result)
+    }
+
     pub async fn update(
         pool: &sqlx::SqlitePool,
         id: i64,
@@ -122,15 +258,40 @@ impl Monitor {
                 url = COALESCE(?, url),
                 type = COALESCE(?, type),
                 interval = COALESCE(?, interval),
-                timeout = COALESCE(?, timeout)
+                timeout = COALESCE(?, timeout),
+                max_retries = COALESCE(?, max_retries),
+                retry_interval = COALESCE(?, retry_interval),
+                config = COALESCE(?, config),
+                conditions = COALESCE(?, conditions),
+                push_token = COALESCE(?, push_token),
+                parent = CASE WHEN ? THEN ? ELSE parent END,
+                proxy_id = CASE WHEN ? THEN ? ELSE proxy_id END,
+                tls_cert = CASE WHEN ? THEN ? ELSE tls_cert END,
+                tls_key = CASE WHEN ? THEN ? ELSE tls_key END,
+                tls_ca = CASE WHEN ? THEN ? ELSE tls_ca END
             WHERE id = ? AND user_id = ?
-            RETURNING id, user_id, name, url, type, interval, timeout, status, last_check, created_at, updated_at
+            RETURNING id, user_id, name, url, type, interval, timeout, max_retries, retry_interval, retries, config as "config: Json<Value>", conditions as "conditions: Json<Vec<Condition>>", push_token, parent, active, proxy_id, tls_cert, tls_key, tls_ca, status, last_check, created_at, updated_at
             "#,
             monitor.name,
             monitor.url,
             monitor.type_,
             monitor.interval,
             monitor.timeout,
+            monitor.max_retries,
+            monitor.retry_interval,
+            monitor.config.map(Json),
+            monitor.conditions.map(Json),
+            monitor.push_token,
+            monitor.parent.is_some(),
+            monitor.parent.flatten(),
+            monitor.proxy_id.is_some(),
+            monitor.proxy_id.flatten(),
+            monitor.tls_cert.is_some(),
+            monitor.tls_cert.flatten(),
+            monitor.tls_key.is_some(),
+            monitor.tls_key.flatten(),
+            monitor.tls_ca.is_some(),
+            monitor.tls_ca.flatten(),
             id,
             user_id
         )
@@ -140,54 +301,205 @@ impl Monitor {
         Ok(result)
     }
 
+    /// Deletes a monitor and, for groups, everything nested under it.
+    ///
+    /// Returns the ids of the deleted monitors.
     pub async fn delete(
         pool: &sqlx::SqlitePool,
         id: i64,
         user_id: i64,
-    ) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query!(
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let result = sqlx::query_scalar!(
             r#"
+            WITH RECURSIVE tree(id) AS (
+                SELECT id FROM monitors WHERE id = ? AND user_id = ?
+                UNION ALL
+                SELECT monitors.id FROM monitors JOIN tree ON monitors.parent = tree.id
+            )
             DELETE FROM monitors
-            WHERE id = ? AND user_id = ?
+            WHERE id IN (SELECT id FROM tree)
+            RETURNING id as "id!"
             "#,
             id,
             user_id
         )
-        .execute(pool)
+        .fetch_all(pool)
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(result)
+    }
+
+    /// Pauses or resumes a monitor and, for groups, everything nested under it.
+    ///
+    /// Returns the updated monitors.
+    pub async fn set_active(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        user_id: i64,
+        active: bool,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Monitor,
+            r#"
+            WITH RECURSIVE tree(id) AS (
+                SELECT id FROM monitors WHERE id = ? AND user_id = ?
+                UNION ALL
+                SELECT monitors.id FROM monitors JOIN tree ON monitors.parent = tree.id
+            )
+            UPDATE monitors
+            SET active = ?
+            WHERE id IN (SELECT id FROM tree)
+            RETURNING id, user_id, name, url, type, interval, timeout, max_retries, retry_interval, retries, config as "config: Json<Value>", conditions as "conditions: Json<Vec<Condition>>", push_token, parent, active, proxy_id, tls_cert, tls_key, tls_ca, status, last_check, created_at, updated_at
+            "#,
+            id,
+            user_id,
+            active
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Ids of every monitor nested under the given one, at any depth.
+    pub async fn descendant_ids(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let result = sqlx::query_scalar!(
+            r#"
+            WITH RECURSIVE tree(id) AS (
+                SELECT id FROM monitors WHERE parent = ?
+                UNION ALL
+                SELECT monitors.id FROM monitors JOIN tree ON monitors.parent = tree.id
+            )
+            SELECT id as "id!" FROM tree
+            "#,
+            id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn list_children(
+        pool: &sqlx::SqlitePool,
+        parent: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Monitor,
+            r#"
+            SELECT id, user_id, name, url, type, interval, timeout, max_retries, retry_interval, retries, config as "config: Json<Value>", conditions as "conditions: Json<Vec<Condition>>", push_token, parent, active, proxy_id, tls_cert, tls_key, tls_ca, status, last_check, created_at, updated_at
+            FROM monitors
+            WHERE parent = ?
+            ORDER BY id
+            "#,
+            parent
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Same as `list_by_user`, but with monitors nested under their group.
+    pub async fn list_tree_by_user(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<MonitorNode>, sqlx::Error> {
+        let monitors = Self::list_by_user(pool, user_id).await?;
+        Ok(Self::build_tree(monitors))
+    }
+
+    /// Nests monitors under their parents, keeping the original order among
+    /// siblings. Monitors whose parent is not in the list become roots, and
+    /// a parent cycle is broken at its first monitor, which becomes a root.
+    pub fn build_tree(monitors: Vec<Self>) -> Vec<MonitorNode> {
+        let order: HashMap<i64, usize> = monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| (monitor.id, index))
+            .collect();
+        let ids: HashSet<i64> = order.keys().copied().collect();
+        let mut children: HashMap<i64, Vec<Self>> = HashMap::new();
+        let mut roots = Vec::new();
+        for monitor in monitors {
+            match monitor.parent.filter(|parent| ids.contains(parent)) {
+                Some(parent) => children.entry(parent).or_default().push(monitor),
+                None => roots.push(monitor),
+            }
+        }
+
+        fn attach(monitor: Monitor, children: &mut HashMap<i64, Vec<Monitor>>) -> MonitorNode {
+            let nested = children.remove(&monitor.id).unwrap_or_default();
+            MonitorNode {
+                children: nested.into_iter().map(|child| attach(child, children)).collect(),
+                monitor,
+            }
+        }
+
+        let mut tree: Vec<MonitorNode> = roots.into_iter().map(|monitor| attach(monitor, &mut children)).collect();
+
+        // Whatever is left hangs off a cycle and was never reached from a root
+        loop {
+            let first = children
+                .iter()
+                .flat_map(|(parent, siblings)| {
+                    siblings.iter().enumerate().map(move |(index, monitor)| (monitor.id, *parent, index))
+                })
+                .min_by_key(|(id, _, _)| order[id]);
+            let Some((_, parent, index)) = first else {
+                break;
+            };
+            let Some(siblings) = children.get_mut(&parent) else {
+                break;
+            };
+            let monitor = siblings.remove(index);
+            if siblings.is_empty() {
+                children.remove(&parent);
+            }
+            tree.push(attach(monitor, &mut children));
+        }
+
+        tree.sort_by_key(|node| order[&node.monitor.id]);
+        tree
     }
 
     pub async fn update_status(
         pool: &sqlx::SqlitePool,
         id: i64,
         status: &str,
+        retries: i32,
         ping: Option<i32>,
         message: Option<&str>,
+        details: Option<&Value>,
     ) -> Result<(), sqlx::Error> {
         sqlx::query!(
             r#"
             UPDATE monitors
-            SET status = ?, last_check = CURRENT_TIMESTAMP
+            SET status = ?, retries = ?, last_check = CURRENT_TIMESTAMP
             WHERE id = ?
             "#,
             status,
+            retries,
             id
         )
         .execute(pool)
         .await?;
 
         if let Some(ping) = ping {
+            let details = details.map(Json);
             sqlx::query!(
                 r#"
-                INSERT INTO monitor_status_history (monitor_id, status, ping, message)
-                VALUES (?, ?, ?, ?)
+                INSERT INTO monitor_status_history (monitor_id, status, ping, message, details)
+                VALUES (?, ?, ?, ?, ?)
                 "#,
                 id,
                 status,
                 ping,
-                message
+                message,
+                details
             )
             .execute(pool)
             .await?;
@@ -195,4 +507,132 @@ impl Monitor {
 
         Ok(())
     }
+
+    /// Details recorded by the most recent check that produced any.
+    pub async fn last_details(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+    ) -> Result<Option<Value>, sqlx::Error> {
+        let result = sqlx::query_scalar!(
+            r#"
+            SELECT details as "details!: Json<Value>"
+            FROM monitor_status_history
+            WHERE monitor_id = ? AND details IS NOT NULL
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result.map(|details| details.0))
+    }
+}
+
+#[cfg(test)]
+impl Monitor {
+    /// Unsaved monitor with default settings.
+    pub fn for_test(id: i64, type_: &str) -> Self {
+        Self {
+            id,
+            user_id: 1,
+            name: format!("Monitor {}", id),
+            url: String::new(),
+            type_: type_.to_string(),
+            interval: 60,
+            timeout: 30,
+            max_retries: 0,
+            retry_interval: 60,
+            retries: 0,
+            config: Json(Value::Object(Default::default())),
+            conditions: Json(Vec::new()),
+            push_token: None,
+            parent: None,
+            active: true,
+            proxy_id: None,
+            tls_cert: None,
+            tls_key: None,
+            tls_ca: None,
+            status: "unknown".to_string(),
+            last_check: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(id: i64, parent: Option<i64>) -> Monitor {
+        Monitor {
+            parent,
+            ..Monitor::for_test(id, "http")
+        }
+    }
+
+    /// (id, children) pairs, for comparing trees.
+    fn shape(nodes: &[MonitorNode]) -> Vec<(i64, Vec<(i64, usize)>)> {
+        nodes
+            .iter()
+            .map(|node| {
+                let children = node
+                    .children
+                    .iter()
+                    .map(|child| (child.monitor.id, child.children.len()))
+                    .collect();
+                (node.monitor.id, children)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nests_children_under_groups() {
+        let tree = Monitor::build_tree(vec![
+            monitor(1, None),
+            monitor(2, Some(1)),
+            monitor(3, Some(2)),
+            monitor(4, None),
+        ]);
+        assert_eq!(shape(&tree), vec![(1, vec![(2, 1)]), (4, vec![])]);
+        assert_eq!(tree[0].children[0].children[0].monitor.id, 3);
+    }
+
+    #[test]
+    fn keeps_sibling_order() {
+        let tree = Monitor::build_tree(vec![
+            monitor(5, Some(1)),
+            monitor(3, None),
+            monitor(1, None),
+            monitor(2, Some(1)),
+            monitor(4, Some(1)),
+        ]);
+        assert_eq!(shape(&tree), vec![(3, vec![]), (1, vec![(5, 0), (2, 0), (4, 0)])]);
+    }
+
+    #[test]
+    fn orphan_becomes_root() {
+        let tree = Monitor::build_tree(vec![monitor(1, None), monitor(2, Some(99))]);
+        assert_eq!(shape(&tree), vec![(1, vec![]), (2, vec![])]);
+    }
+
+    #[test]
+    fn cycle_is_broken_at_first_monitor() {
+        let tree = Monitor::build_tree(vec![
+            monitor(1, None),
+            monitor(2, Some(3)),
+            monitor(3, Some(2)),
+            monitor(4, Some(3)),
+        ]);
+        assert_eq!(shape(&tree), vec![(1, vec![]), (2, vec![(3, 1)])]);
+        assert_eq!(tree[1].children[0].children[0].monitor.id, 4);
+    }
+
+    #[test]
+    fn self_parent_becomes_root() {
+        let tree = Monitor::build_tree(vec![monitor(1, Some(1)), monitor(2, Some(1))]);
+        assert_eq!(shape(&tree), vec![(1, vec![(2, 0)])]);
+    }
 }
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, types::Json};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Notification {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub config: Json<Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub async fn list_by_user(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Notification,
+            r#"
+            SELECT id, user_id, name, type as "type_", config as "config: Json<Value>", created_at, updated_at
+            FROM notifications
+            WHERE user_id = ?
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Whether a notification of this kind was already sent for the monitor
+    /// at or below the given number of days.
+    pub async fn was_sent(
+        pool: &sqlx::SqlitePool,
+        type_: &str,
+        monitor_id: i64,
+        days: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            SELECT id
+            FROM notification_sent_history
+            WHERE type = ? AND monitor_id = ? AND days <= ?
+            "#,
+            type_,
+            monitor_id,
+            days
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result.is_some())
+    }
+
+    pub async fn mark_sent(
+        pool: &sqlx::SqlitePool,
+        type_: &str,
+        monitor_id: i64,
+        days: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO notification_sent_history (type, monitor_id, days)
+            VALUES (?, ?, ?)
+            "#,
+            type_,
+            monitor_id,
+            days
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn clear_sent(
+        pool: &sqlx::SqlitePool,
+        type_: &str,
+        monitor_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            DELETE FROM notification_sent_history
+            WHERE type = ? AND monitor_id = ?
+            "#,
+            type_,
+            monitor_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+
+pub const PROTOCOLS: &[&str] = &["http", "https", "socks5", "socks5h"];
+
+/// Proxy that HTTP based monitors can send their requests through.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Proxy {
+    pub id: i64,
+    pub user_id: i64,
+    pub protocol: String,
+    pub host: String,
+    pub port: i32,
+    pub auth: bool,
+    pub username: Option<String>,
+    #[serde(skip_serializing)]
+    pub password: Option<String>,
+    /// Used for new monitors that do not pick a proxy.
+    #[serde(rename = "default")]
+    pub is_default: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateProxy {
+    pub protocol: String,
+    pub host: String,
+    pub port: i32,
+    #[serde(default)]
+    pub auth: bool,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    #[serde(default, rename = "default")]
+    pub is_default: bool,
+    /// Also use this proxy for every existing monitor.
+    #[serde(default)]
+    pub apply_existing: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateProxy {
+    pub protocol: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<i32>,
+    pub auth: Option<bool>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    #[serde(rename = "default")]
+    pub is_default: Option<bool>,
+    #[serde(default)]
+    pub apply_existing: bool,
+}
+
+impl Proxy {
+    /// URL understood by `reqwest::Proxy`, including the credentials.
+    pub fn url(&self) -> Result<String, String> {
+        let mut url = Url::parse(&format!("{}://{}:{}", self.protocol, self.host, self.port))
+            .map_err(|e| format!("Invalid proxy: {}", e))?;
+        if self.auth {
+            url.set_username(self.username.as_deref().unwrap_or_default())
+                .and_then(|_| url.set_password(self.password.as_deref()))
+                .map_err(|_| "Invalid proxy credentials".to_string())?;
+        }
+        Ok(url.to_string())
+    }
+
+    pub async fn create(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+        proxy: CreateProxy,
+    ) -> Result<Self, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Proxy,
+            r#"
+            INSERT INTO proxies (user_id, protocol, host, port, auth, username, password, is_default)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING id, user_id, protocol, host, port, auth, username, password, is_default, created_at, updated_at
+            "#,
+            user_id,
+            proxy.protocol,
+            proxy.host,
+            proxy.port,
+            proxy.auth,
+            proxy.username,
+            proxy.password,
+            proxy.is_default
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn find_by_id(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        user_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Proxy,
+            r#"
+            SELECT id, user_id, protocol, host, port, auth, username, password, is_default, created_at, updated_at
+            FROM proxies
+            WHERE id = ? AND user_id = ?
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn find(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Proxy,
+            r#"
+            SELECT id, user_id, protocol, host, port, auth, username, password, is_default, created_at, updated_at
+            FROM proxies
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn find_default(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Proxy,
+            r#"
+            SELECT id, user_id, protocol, host, port, auth, username, password, is_default, created_at, updated_at
+            FROM proxies
+            WHERE user_id = ? AND is_default = 1
+            LIMIT 1
+            "#,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn list_by_user(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Proxy,
+            r#"
+            SELECT id, user_id, protocol, host, port, auth, username, password, is_default, created_at, updated_at
+            FROM proxies
+            WHERE user_id = ?
+            ORDER BY id
+            "#,
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    pub async fn update(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        user_id: i64,
+        proxy: UpdateProxy,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_as!(
+            Proxy,
+            r#"
+            UPDATE proxies
+            SET
+                protocol = COALESCE(?, protocol),
+                host = COALESCE(?, host),
+                port = COALESCE(?, port),
+                auth = COALESCE(?, auth),
+                username = COALESCE(?, username),
+                password = COALESCE(?, password),
+                is_default = COALESCE(?, is_default)
+            WHERE id = ? AND user_id = ?
+            RETURNING id, user_id, protocol, host, port, auth, username, password, is_default, created_at, updated_at
+            "#,
+            proxy.protocol,
+            proxy.host,
+            proxy.port,
+            proxy.auth,
+            proxy.username,
+            proxy.password,
+            proxy.is_default,
+            id,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    /// Deletes the proxy; monitors using it go back to connecting directly.
+    pub async fn delete(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        user_id: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE monitors
+            SET proxy_id = NULL
+            WHERE proxy_id = ? AND user_id = ?
+            "#,
+            id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM proxies
+            WHERE id = ? AND user_id = ?
+            "#,
+            id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Makes sure only one of the user's proxies is the default.
+    pub async fn clear_default(
+        pool: &sqlx::SqlitePool,
+        user_id: i64,
+        except_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE proxies
+            SET is_default = 0
+            WHERE user_id = ? AND id != ? AND is_default = 1
+            "#,
+            user_id,
+            except_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Points every monitor of the user at this proxy.
+    pub async fn apply_to_all_monitors(
+        pool: &sqlx::SqlitePool,
+        id: i64,
+        user_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE monitors
+            SET proxy_id = ?
+            WHERE user_id = ?
+            "#,
+            id,
+            user_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use chrono::{DateTime, Utc};
+
+/// Certificate chain presented by an HTTPS monitor's server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsInfo {
+    /// Whether the chain verified against the system's trusted roots.
+    pub valid: bool,
+    /// Verification error, if the chain did not verify.
+    pub error: Option<String>,
+    /// Certificates as sent by the server, leaf first.
+    pub chain: Vec<CertificateInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    /// "server", "intermediate CA" or "root CA".
+    pub cert_type: String,
+    pub subject: String,
+    pub common_name: Option<String>,
+    pub issuer: String,
+    pub subject_alt_names: Vec<String>,
+    pub serial: String,
+    pub valid_from: DateTime<Utc>,
+    pub valid_to: DateTime<Utc>,
+    pub days_remaining: i64,
+}
+
+impl TlsInfo {
+    pub fn leaf(&self) -> Option<&CertificateInfo> {
+        self.chain.first()
+    }
+
+    pub async fn find_by_monitor(
+        pool: &sqlx::SqlitePool,
+        monitor_id: i64,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let result = sqlx::query_scalar!(
+            r#"
+            SELECT info_json as "info_json: Json<TlsInfo>"
+            FROM monitor_tls_info
+            WHERE monitor_id = ?
+            "#,
+            monitor_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(result.map(|info| info.0))
+    }
+
+    pub async fn save(
+        &self,
+        pool: &sqlx::SqlitePool,
+        monitor_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        let info_json = Json(self);
+        sqlx::query!(
+            r#"
+            INSERT INTO monitor_tls_info (monitor_id, info_json)
+            VALUES (?, ?)
+            ON CONFLICT (monitor_id) DO UPDATE SET info_json = excluded.info_json
+            "#,
+            monitor_id,
+            info_json
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
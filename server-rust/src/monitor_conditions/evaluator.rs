@@ -0,0 +1,231 @@
+use serde_json::{Map, Value};
+use super::{
+    expression::{AndOr, Condition},
+    variables::ConditionVariable,
+};
+
+/// Evaluates a monitor's conditions against the variables of a check.
+///
+/// Conditions are combined left to right with their `and_or`, groups acting
+/// as parentheses. An empty list always holds. When the conditions do not
+/// hold the error lists the failed expressions that made them false, with
+/// the actual values.
+pub fn evaluate(conditions: &[Condition], variables: &Map<String, Value>) -> Result<(), String> {
+    let mut failed = Vec::new();
+    if evaluate_list(conditions, variables, &mut failed) {
+        Ok(())
+    } else {
+        Err(format!("Condition not met: {}", failed.join("; ")))
+    }
+}
+
+fn evaluate_list(conditions: &[Condition], variables: &Map<String, Value>, failed: &mut Vec<String>) -> bool {
+    let mut result = true;
+    // Failures behind the current result, dropped once a branch makes it true
+    let mut culprits = Vec::new();
+    for (index, condition) in conditions.iter().enumerate() {
+        let mut branch = Vec::new();
+        let value = evaluate_condition(condition, variables, &mut branch);
+        result = match condition.and_or() {
+            _ if index == 0 => value,
+            AndOr::And => result && value,
+            AndOr::Or => result || value,
+        };
+        if result {
+            culprits.clear();
+        } else {
+            culprits.append(&mut branch);
+        }
+    }
+    failed.append(&mut culprits);
+    result
+}
+
+fn evaluate_condition(condition: &Condition, variables: &Map<String, Value>, failed: &mut Vec<String>) -> bool {
+    match condition {
+        Condition::Expression(expression) => {
+            let Some(actual) = variables.get(&expression.variable) else {
+                failed.push(format!("{} ({} is not set)", expression, expression.variable));
+                return false;
+            };
+
+            let holds = expression.operator.test(actual, &expression.value);
+            if !holds {
+                failed.push(format!("{} ({} was {})", expression, expression.variable, actual));
+            }
+            holds
+        }
+        Condition::Group(group) => evaluate_list(&group.children, variables, failed),
+    }
+}
+
+/// Checks that every expression uses a variable of the monitor type with
+/// an operator that variable supports.
+pub fn validate(conditions: &[Condition], variables: &[ConditionVariable]) -> Result<(), String> {
+    for condition in conditions {
+        match condition {
+            Condition::Expression(expression) => {
+                let variable = variables
+                    .iter()
+                    .find(|variable| variable.id == expression.variable)
+                    .ok_or_else(|| format!("Unknown condition variable: {}", expression.variable))?;
+                if !variable.operators.contains(&expression.operator) {
+                    return Err(format!(
+                        "Operator {} is not supported for {}",
+                        expression.operator, expression.variable
+                    ));
+                }
+            }
+            Condition::Group(group) => validate(&group.children, variables)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor_conditions::{
+        expression::{ConditionExpression, ConditionGroup},
+        operators::{Operator, NUMBER_OPERATORS, STRING_OPERATORS},
+    };
+    use serde_json::json;
+
+    fn expression(variable: &str, operator: Operator, value: &str, and_or: AndOr) -> Condition {
+        Condition::Expression(ConditionExpression {
+            variable: variable.to_string(),
+            operator,
+            value: value.to_string(),
+            and_or,
+        })
+    }
+
+    fn group(children: Vec<Condition>, and_or: AndOr) -> Condition {
+        Condition::Group(ConditionGroup { children, and_or })
+    }
+
+    fn variables() -> Map<String, Value> {
+        json!({ "a": 1, "b": 2, "c": 3 }).as_object().unwrap().clone()
+    }
+
+    /// `name == value`, true when `value` is the variable's actual value.
+    fn check(name: &str, value: i64, and_or: AndOr) -> Condition {
+        expression(name, Operator::NumEquals, &value.to_string(), and_or)
+    }
+
+    #[test]
+    fn empty_list_holds() {
+        assert_eq!(evaluate(&[], &variables()), Ok(()));
+    }
+
+    #[test]
+    fn combines_left_to_right() {
+        // a OR b AND c is (a OR b) AND c
+        let conditions = [check("a", 0, AndOr::And), check("b", 2, AndOr::Or), check("c", 0, AndOr::And)];
+        assert_eq!(evaluate(&conditions, &variables()), Err("Condition not met: c == 0 (c was 3)".to_string()));
+
+        // a AND b OR c is (a AND b) OR c
+        let conditions = [check("a", 0, AndOr::And), check("b", 2, AndOr::And), check("c", 3, AndOr::Or)];
+        assert_eq!(evaluate(&conditions, &variables()), Ok(()));
+    }
+
+    #[test]
+    fn reports_every_false_operand() {
+        let conditions = [check("a", 0, AndOr::And), check("b", 0, AndOr::Or)];
+        assert_eq!(
+            evaluate(&conditions, &variables()),
+            Err("Condition not met: a == 0 (a was 1); b == 0 (b was 2)".to_string())
+        );
+
+        let conditions = [check("a", 0, AndOr::And), check("b", 2, AndOr::And)];
+        assert_eq!(evaluate(&conditions, &variables()), Err("Condition not met: a == 0 (a was 1)".to_string()));
+    }
+
+    #[test]
+    fn true_group_hides_its_failures() {
+        // (a OR b) AND c with a false, b true and c false
+        let conditions = [
+            group(vec![check("a", 0, AndOr::And), check("b", 2, AndOr::Or)], AndOr::And),
+            check("c", 0, AndOr::And),
+        ];
+        assert_eq!(evaluate(&conditions, &variables()), Err("Condition not met: c == 0 (c was 3)".to_string()));
+    }
+
+    #[test]
+    fn nested_groups() {
+        // a AND (b OR (c AND a))
+        let inner = group(vec![check("c", 3, AndOr::And), check("a", 1, AndOr::And)], AndOr::Or);
+        let conditions = [
+            check("a", 1, AndOr::And),
+            group(vec![check("b", 0, AndOr::And), inner], AndOr::And),
+        ];
+        assert_eq!(evaluate(&conditions, &variables()), Ok(()));
+
+        let inner = group(vec![check("c", 0, AndOr::And), check("a", 1, AndOr::And)], AndOr::Or);
+        let conditions = [
+            check("a", 1, AndOr::And),
+            group(vec![check("b", 0, AndOr::And), inner], AndOr::And),
+        ];
+        assert_eq!(
+            evaluate(&conditions, &variables()),
+            Err("Condition not met: b == 0 (b was 2); c == 0 (c was 3)".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_variable_fails() {
+        let conditions = [expression("record", Operator::Contains, "1.2.3.4", AndOr::And)];
+        assert_eq!(
+            evaluate(&conditions, &variables()),
+            Err("Condition not met: record contains \"1.2.3.4\" (record is not set)".to_string())
+        );
+
+        // Unless another branch holds
+        let conditions = [
+            expression("record", Operator::Contains, "1.2.3.4", AndOr::And),
+            check("a", 1, AndOr::Or),
+        ];
+        assert_eq!(evaluate(&conditions, &variables()), Ok(()));
+    }
+
+    #[test]
+    fn validate_checks_variables_and_operators() {
+        let variables = [
+            ConditionVariable::new("record", STRING_OPERATORS),
+            ConditionVariable::new("ttl", NUMBER_OPERATORS),
+        ];
+
+        let valid = [
+            expression("record", Operator::Contains, "1.2.3.4", AndOr::And),
+            group(vec![expression("ttl", Operator::Gt, "60", AndOr::And)], AndOr::Or),
+        ];
+        assert_eq!(validate(&valid, &variables), Ok(()));
+
+        let unknown = [group(vec![expression("status", Operator::Equals, "ok", AndOr::And)], AndOr::And)];
+        assert_eq!(
+            validate(&unknown, &variables),
+            Err("Unknown condition variable: status".to_string())
+        );
+
+        let wrong_operator = [expression("ttl", Operator::Contains, "6", AndOr::And)];
+        assert_eq!(
+            validate(&wrong_operator, &variables),
+            Err("Operator contains is not supported for ttl".to_string())
+        );
+    }
+
+    #[test]
+    fn deserializes_conditions() {
+        let conditions: Vec<Condition> = serde_json::from_value(json!([
+            { "type": "expression", "variable": "a", "operator": "num_equals", "value": "1" },
+            {
+                "type": "group",
+                "and_or": "or",
+                "children": [{ "type": "expression", "variable": "b", "operator": "lt", "value": "0" }]
+            }
+        ]))
+        .unwrap();
+        assert_eq!(conditions[1].and_or(), AndOr::Or);
+        assert_eq!(evaluate(&conditions, &variables()), Ok(()));
+    }
+}
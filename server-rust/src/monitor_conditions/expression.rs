@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use super::operators::{Operator, NUMBER_OPERATORS};
+
+/// How a condition combines with the result of the conditions before it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AndOr {
+    #[default]
+    And,
+    Or,
+}
+
+/// One entry of a monitor's condition list: a comparison or a nested group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Condition {
+    Expression(ConditionExpression),
+    Group(ConditionGroup),
+}
+
+impl Condition {
+    pub fn and_or(&self) -> AndOr {
+        match self {
+            Condition::Expression(expression) => expression.and_or,
+            Condition::Group(group) => group.and_or,
+        }
+    }
+}
+
+/// Compares a variable of the check result with a value, e.g. `ttl > 60`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionExpression {
+    pub variable: String,
+    pub operator: Operator,
+    pub value: String,
+    #[serde(default)]
+    pub and_or: AndOr,
+}
+
+/// Conditions evaluated together, like parentheses in an expression.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionGroup {
+    pub children: Vec<Condition>,
+    #[serde(default)]
+    pub and_or: AndOr,
+}
+
+impl fmt::Display for AndOr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AndOr::And => f.write_str("AND"),
+            AndOr::Or => f.write_str("OR"),
+        }
+    }
+}
+
+impl fmt::Display for ConditionExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if NUMBER_OPERATORS.contains(&self.operator) {
+            write!(f, "{} {} {}", self.variable, self.operator, self.value)
+        } else {
+            write!(f, "{} {} {:?}", self.variable, self.operator, self.value)
+        }
+    }
+}
+
+impl fmt::Display for ConditionGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({})", DisplayList(&self.children))
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Expression(expression) => expression.fmt(f),
+            Condition::Group(group) => group.fmt(f),
+        }
+    }
+}
+
+/// Formats a condition list the way a user would write it, e.g.
+/// `record contains "1.2.3.4" AND ttl > 60`.
+pub struct DisplayList<'a>(pub &'a [Condition]);
+
+impl fmt::Display for DisplayList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, condition) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " {} ", condition.and_or())?;
+            }
+            condition.fmt(f)?;
+        }
+        Ok(())
+    }
+}
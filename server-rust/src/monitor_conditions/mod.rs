@@ -0,0 +1,5 @@
+// Monitor conditions module
+pub mod evaluator;
+pub mod expression;
+pub mod operators;
+pub mod variables;
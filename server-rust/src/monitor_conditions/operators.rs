@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// Comparison between a check variable and the value given in a condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operator {
+    Equals,
+    NotEquals,
+    Contains,
+    NotContains,
+    StartsWith,
+    NotStartsWith,
+    EndsWith,
+    NotEndsWith,
+    NumEquals,
+    NumNotEquals,
+    Lt,
+    Gt,
+    Lte,
+    Gte,
+}
+
+pub const STRING_OPERATORS: &[Operator] = &[
+    Operator::Equals,
+    Operator::NotEquals,
+    Operator::Contains,
+    Operator::NotContains,
+    Operator::StartsWith,
+    Operator::NotStartsWith,
+    Operator::EndsWith,
+    Operator::NotEndsWith,
+];
+
+pub const NUMBER_OPERATORS: &[Operator] = &[
+    Operator::NumEquals,
+    Operator::NumNotEquals,
+    Operator::Lt,
+    Operator::Gt,
+    Operator::Lte,
+    Operator::Gte,
+];
+
+impl Operator {
+    /// Whether the operator holds for the variable's value.
+    ///
+    /// List values (e.g. all DNS records) match when any item does, and the
+    /// `not_*` operators hold only when no item does.
+    pub fn test(self, actual: &Value, expected: &str) -> bool {
+        match self.negated() {
+            Some(positive) => !positive.test(actual, expected),
+            None => match actual {
+                Value::Array(items) => items.iter().any(|item| self.test_one(item, expected)),
+                _ => self.test_one(actual, expected),
+            },
+        }
+    }
+
+    /// The positive operator a `not_*` operator negates.
+    fn negated(self) -> Option<Self> {
+        match self {
+            Operator::NotEquals => Some(Operator::Equals),
+            Operator::NotContains => Some(Operator::Contains),
+            Operator::NotStartsWith => Some(Operator::StartsWith),
+            Operator::NotEndsWith => Some(Operator::EndsWith),
+            Operator::NumNotEquals => Some(Operator::NumEquals),
+            _ => None,
+        }
+    }
+
+    fn test_one(self, actual: &Value, expected: &str) -> bool {
+        let text = match actual {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+
+        match self {
+            Operator::Equals => text == expected,
+            Operator::Contains => text.contains(expected),
+            Operator::StartsWith => text.starts_with(expected),
+            Operator::EndsWith => text.ends_with(expected),
+            _ => {
+                let (Ok(actual), Ok(expected)) = (text.trim().parse::<f64>(), expected.trim().parse::<f64>()) else {
+                    return false;
+                };
+                match self {
+                    Operator::NumEquals => actual == expected,
+                    Operator::Lt => actual < expected,
+                    Operator::Gt => actual > expected,
+                    Operator::Lte => actual <= expected,
+                    Operator::Gte => actual >= expected,
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Operator::Equals | Operator::NumEquals => "==",
+            Operator::NotEquals | Operator::NumNotEquals => "!=",
+            Operator::Contains => "contains",
+            Operator::NotContains => "not contains",
+            Operator::StartsWith => "starts with",
+            Operator::NotStartsWith => "not starts with",
+            Operator::EndsWith => "ends with",
+            Operator::NotEndsWith => "not ends with",
+            Operator::Lt => "<",
+            Operator::Gt => ">",
+            Operator::Lte => "<=",
+            Operator::Gte => ">=",
+        };
+        f.write_str(symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn string_operators() {
+        let actual = json!("example.com");
+        assert!(Operator::Equals.test(&actual, "example.com"));
+        assert!(Operator::NotEquals.test(&actual, "example.org"));
+        assert!(Operator::Contains.test(&actual, "ample"));
+        assert!(!Operator::NotContains.test(&actual, "ample"));
+        assert!(Operator::StartsWith.test(&actual, "exa"));
+        assert!(Operator::NotStartsWith.test(&actual, "com"));
+        assert!(Operator::EndsWith.test(&actual, ".com"));
+        assert!(!Operator::NotEndsWith.test(&actual, ".com"));
+    }
+
+    #[test]
+    fn number_operators() {
+        let actual = json!(300);
+        assert!(Operator::NumEquals.test(&actual, "300"));
+        assert!(Operator::NumEquals.test(&actual, " 300.0 "));
+        assert!(Operator::NumNotEquals.test(&actual, "60"));
+        assert!(Operator::Gt.test(&actual, "60"));
+        assert!(!Operator::Lt.test(&actual, "60"));
+        assert!(Operator::Gte.test(&actual, "300"));
+        assert!(Operator::Lte.test(&actual, "300"));
+
+        // Numbers reported as strings still compare numerically
+        assert!(Operator::Lt.test(&json!("9"), "10"));
+    }
+
+    #[test]
+    fn non_numbers_never_compare() {
+        assert!(!Operator::Gt.test(&json!("abc"), "1"));
+        assert!(!Operator::Lt.test(&json!(1), "abc"));
+        assert!(!Operator::NumEquals.test(&Value::Null, "0"));
+        assert!(Operator::NumNotEquals.test(&json!("abc"), "1"));
+    }
+
+    #[test]
+    fn lists_match_any_item() {
+        let records = json!(["1.2.3.4", "5.6.7.8"]);
+        assert!(Operator::Equals.test(&records, "5.6.7.8"));
+        assert!(Operator::StartsWith.test(&records, "1.2."));
+        assert!(!Operator::Equals.test(&records, "9.9.9.9"));
+
+        // Negated operators hold only when no item matches
+        assert!(!Operator::NotEquals.test(&records, "5.6.7.8"));
+        assert!(Operator::NotEquals.test(&records, "9.9.9.9"));
+        assert!(!Operator::NotContains.test(&records, "1.2"));
+    }
+
+    #[test]
+    fn operator_sets_are_disjoint() {
+        for operator in STRING_OPERATORS {
+            assert!(!NUMBER_OPERATORS.contains(operator), "{:?}", operator);
+        }
+    }
+
+    #[test]
+    fn serializes_as_snake_case() {
+        assert_eq!(serde_json::to_value(Operator::NotStartsWith).unwrap(), json!("not_starts_with"));
+        assert_eq!(serde_json::from_value::<Operator>(json!("gte")).unwrap(), Operator::Gte);
+    }
+}
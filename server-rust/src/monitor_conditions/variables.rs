@@ -0,0 +1,15 @@
+use super::operators::Operator;
+
+/// A value a monitor type exposes to conditions, e.g. `record` for DNS.
+#[derive(Debug, Clone, Copy)]
+pub struct ConditionVariable {
+    pub id: &'static str,
+    /// Operators that make sense for this variable.
+    pub operators: &'static [Operator],
+}
+
+impl ConditionVariable {
+    pub const fn new(id: &'static str, operators: &'static [Operator]) -> Self {
+        Self { id, operators }
+    }
+}
@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::{Connection, MySqlConnection, PgConnection};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_util::compat::TokioAsyncWriteCompatExt;
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config};
+
+#[derive(Debug, Deserialize)]
+struct DatabaseConfig {
+    /// URL for Postgres and MySQL, ADO.NET string for SQL Server.
+    connection_string: String,
+    #[serde(default = "default_query")]
+    query: String,
+}
+
+fn default_query() -> String {
+    "SELECT 1".to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Database {
+    Postgres,
+    MySql,
+    SqlServer,
+}
+
+/// Connects to a database and runs a query, down on any error or when the
+/// query returns no rows.
+pub struct DatabaseMonitorType {
+    database: Database,
+}
+
+impl DatabaseMonitorType {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    async fn run(&self, config: &DatabaseConfig) -> Result<usize, String> {
+        match self.database {
+            Database::Postgres => {
+                let mut connection = PgConnection::connect(&config.connection_string)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let rows = sqlx::query(&config.query)
+                    .fetch_all(&mut connection)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let _ = connection.close().await;
+                Ok(rows.len())
+            }
+            Database::MySql => {
+                let mut connection = MySqlConnection::connect(&config.connection_string)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let rows = sqlx::query(&config.query)
+                    .fetch_all(&mut connection)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let _ = connection.close().await;
+                Ok(rows.len())
+            }
+            Database::SqlServer => {
+                let sql_config = tiberius::Config::from_ado_string(&config.connection_string)
+                    .map_err(|e| e.to_string())?;
+                let tcp = TcpStream::connect(sql_config.get_addr())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                tcp.set_nodelay(true).map_err(|e| e.to_string())?;
+
+                let mut client = tiberius::Client::connect(sql_config, tcp.compat_write())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let rows = client
+                    .simple_query(config.query.as_str())
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .into_first_result()
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let _ = client.close().await;
+                Ok(rows.len())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl MonitorType for DatabaseMonitorType {
+    fn name(&self) -> &'static str {
+        match self.database {
+            Database::Postgres => "postgres",
+            Database::MySql => "mysql",
+            Database::SqlServer => "sqlserver",
+        }
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: DatabaseConfig = parse_config(config)?;
+        if config.connection_string.trim().is_empty() {
+            return Err(AppError::BadRequest("Connection string is required".to_string()));
+        }
+        if config.query.trim().is_empty() {
+            return Err(AppError::BadRequest("Query is required".to_string()));
+        }
+
+        let valid = match self.database {
+            Database::Postgres => ["postgres://", "postgresql://"]
+                .iter()
+                .any(|scheme| config.connection_string.starts_with(scheme)),
+            Database::MySql => ["mysql://", "mariadb://"]
+                .iter()
+                .any(|scheme| config.connection_string.starts_with(scheme)),
+            Database::SqlServer => tiberius::Config::from_ado_string(&config.connection_string).is_ok(),
+        };
+        if !valid {
+            return Err(AppError::BadRequest(format!(
+                "Invalid {} connection string",
+                self.name()
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: DatabaseConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid database config"),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let start_time = Instant::now();
+        let rows = match tokio::time::timeout(timeout, self.run(&config)).await {
+            Ok(Ok(rows)) => rows,
+            Ok(Err(e)) => return CheckResult::down(e),
+            Err(_) => return CheckResult::down(format!("Timed out after {}s", timeout.as_secs())),
+        };
+        let latency = start_time.elapsed().as_millis() as i32;
+
+        let result = if rows == 0 {
+            CheckResult::down("Query returned no rows")
+        } else {
+            CheckResult::up().with_message(format!("Rows: {}", rows))
+        };
+        result.with_latency(latency)
+    }
+}
@@ -0,0 +1,190 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use trust_dns_resolver::{
+    config::{NameServerConfigGroup, ResolverConfig, ResolverOpts},
+    proto::rr::RecordType,
+    TokioAsyncResolver,
+};
+use crate::{
+    models::monitor::Monitor,
+    monitor_conditions::{
+        operators::{NUMBER_OPERATORS, STRING_OPERATORS},
+        variables::ConditionVariable,
+    },
+    services::notification::NotificationService,
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config};
+
+pub const RECORD_TYPES: &[&str] = &["A", "AAAA", "CNAME", "MX", "NS", "TXT", "SRV", "SOA", "CAA"];
+
+const CONDITION_VARIABLES: &[ConditionVariable] = &[
+    ConditionVariable::new("record", STRING_OPERATORS),
+    ConditionVariable::new("ttl", NUMBER_OPERATORS),
+];
+
+#[derive(Debug, Deserialize)]
+struct DnsConfig {
+    hostname: String,
+    #[serde(default = "default_resolver_server")]
+    resolver_server: String,
+    #[serde(default = "default_resolver_port")]
+    resolver_port: u16,
+    #[serde(default = "default_record_type")]
+    record_type: String,
+    /// Down unless one of the records matches this value.
+    expected_value: Option<String>,
+    /// Notify when the set of records differs from the previous check.
+    #[serde(default)]
+    notify_on_change: bool,
+}
+
+fn default_resolver_server() -> String {
+    "1.1.1.1".to_string()
+}
+
+fn default_resolver_port() -> u16 {
+    53
+}
+
+fn default_record_type() -> String {
+    "A".to_string()
+}
+
+/// Resolves a record against a configured DNS server.
+pub struct DnsMonitorType {
+    pool: SqlitePool,
+    notifications: Arc<NotificationService>,
+}
+
+impl DnsMonitorType {
+    pub fn new(pool: SqlitePool, notifications: Arc<NotificationService>) -> Self {
+        Self { pool, notifications }
+    }
+
+    /// Sends a notification when the records differ from the last check.
+    async fn notify_changes(&self, monitor: &Monitor, hostname: &str, records: &[String]) -> Result<(), AppError> {
+        let previous = Monitor::last_details(&self.pool, monitor.id)
+            .await?
+            .and_then(|details| serde_json::from_value::<Vec<String>>(details["records"].clone()).ok());
+
+        if let Some(previous) = previous {
+            if previous != records {
+                let message = format!(
+                    "[{}] DNS records for {} changed from [{}] to [{}]",
+                    monitor.name,
+                    hostname,
+                    previous.join(" | "),
+                    records.join(" | ")
+                );
+                self.notifications.send(monitor, &message).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MonitorType for DnsMonitorType {
+    fn name(&self) -> &'static str {
+        "dns"
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: DnsConfig = parse_config(config)?;
+        if config.hostname.trim().is_empty() {
+            return Err(AppError::BadRequest("Hostname is required".to_string()));
+        }
+        IpAddr::from_str(&config.resolver_server).map_err(|_| {
+            AppError::BadRequest(format!("Resolver server must be an IP address: {}", config.resolver_server))
+        })?;
+        if !RECORD_TYPES.contains(&config.record_type.as_str()) {
+            return Err(AppError::BadRequest(format!("Unsupported record type: {}", config.record_type)));
+        }
+        Ok(())
+    }
+
+    fn condition_variables(&self) -> &'static [ConditionVariable] {
+        CONDITION_VARIABLES
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: DnsConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid DNS config"),
+        };
+        let (Ok(server), Ok(record_type)) = (
+            IpAddr::from_str(&config.resolver_server),
+            RecordType::from_str(&config.record_type),
+        ) else {
+            return CheckResult::down("Invalid DNS config");
+        };
+
+        let resolver_config = ResolverConfig::from_parts(
+            None,
+            vec![],
+            NameServerConfigGroup::from_ips_clear(&[server], config.resolver_port, true),
+        );
+        let mut options = ResolverOpts::default();
+        options.timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        options.attempts = 1;
+        options.cache_size = 0;
+        options.use_hosts_file = false;
+
+        let resolver = match TokioAsyncResolver::tokio(resolver_config, options) {
+            Ok(resolver) => resolver,
+            Err(e) => return CheckResult::down(e.to_string()),
+        };
+
+        let start_time = Instant::now();
+        let lookup = match resolver.lookup(config.hostname.as_str(), record_type).await {
+            Ok(lookup) => lookup,
+            Err(e) => return CheckResult::down(e.to_string()),
+        };
+        let latency = start_time.elapsed().as_millis() as i32;
+
+        let mut records: Vec<String> = lookup.iter().map(|record| record.to_string()).collect();
+        records.sort();
+        let ttl = lookup.record_iter().map(|record| record.ttl()).min();
+
+        if config.notify_on_change {
+            if let Err(e) = self.notify_changes(monitor, &config.hostname, &records).await {
+                tracing::error!("Cannot send DNS change notification for monitor #{}: {:?}", monitor.id, e);
+            }
+        }
+
+        let message = format!("Records: {}", records.join(" | "));
+        let result = match &config.expected_value {
+            Some(expected) if !records.iter().any(|record| matches(record, expected)) => {
+                CheckResult::down(format!("{}, expected {}", message, expected))
+            }
+            _ => CheckResult::up().with_message(message),
+        };
+
+        let result = match ttl {
+            Some(ttl) => result.with_variable("ttl", ttl),
+            None => result,
+        };
+        result
+            .with_latency(latency)
+            .with_variable("record", records.clone())
+            .with_details(json!({ "records": records }))
+    }
+}
+
+/// Whether a record equals the expected value, or has a field that does
+/// (e.g. the exchange of an MX record). Trailing dots are ignored.
+fn matches(record: &str, expected: &str) -> bool {
+    let expected = expected.trim().trim_end_matches('.');
+    record.trim_end_matches('.') == expected
+        || record
+            .split_whitespace()
+            .any(|field| field.trim_matches('"').trim_end_matches('.') == expected)
+}
@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use bollard::{
+    container::ListContainersOptions,
+    models::HealthStatusEnum,
+    Docker, API_DEFAULT_VERSION,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::time::{Duration, Instant};
+use crate::{
+    models::{
+        docker_host::{DockerHost, DOCKER_TYPE_SOCKET, DOCKER_TYPE_TCP},
+        monitor::Monitor,
+    },
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config};
+
+#[derive(Debug, Deserialize)]
+struct DockerConfig {
+    /// Id of the `DockerHost` running the container.
+    docker_host: i64,
+    /// Container name or id.
+    docker_container: String,
+}
+
+/// Checks that a container on a Docker host is running and, if it has a
+/// health check, healthy. Mirrors the "docker" type of the Node server.
+pub struct DockerMonitorType {
+    pool: SqlitePool,
+}
+
+impl DockerMonitorType {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Id of the `DockerHost` a docker monitor's config points at.
+pub fn docker_host_id(config: &Value) -> Result<i64, AppError> {
+    let config: DockerConfig = parse_config(config)?;
+    Ok(config.docker_host)
+}
+
+#[async_trait]
+impl MonitorType for DockerMonitorType {
+    fn name(&self) -> &'static str {
+        "docker"
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: DockerConfig = parse_config(config)?;
+        if config.docker_container.trim().is_empty() {
+            return Err(AppError::BadRequest("Container name is required".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: DockerConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid docker config"),
+        };
+        let host = match DockerHost::find_by_id(&self.pool, config.docker_host, monitor.user_id).await {
+            Ok(Some(host)) => host,
+            Ok(None) => return CheckResult::down("Docker host not found"),
+            Err(e) => return CheckResult::down(e.to_string()),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let docker = match connect(&host, timeout) {
+            Ok(docker) => docker,
+            Err(e) => return CheckResult::down(e),
+        };
+
+        let start_time = Instant::now();
+        let container = match docker.inspect_container(&config.docker_container, None).await {
+            Ok(container) => container,
+            Err(e) => return CheckResult::down(e.to_string()),
+        };
+        let latency = start_time.elapsed().as_millis() as i32;
+
+        let state = container.state.unwrap_or_default();
+        if state.running != Some(true) {
+            let status = state.status.map(|status| status.to_string()).unwrap_or_default();
+            return CheckResult::down(format!("Container State is {}", status)).with_latency(latency);
+        }
+
+        let result = match state.health.and_then(|health| health.status) {
+            // No health check configured
+            None | Some(HealthStatusEnum::EMPTY) | Some(HealthStatusEnum::NONE) => {
+                CheckResult::up().with_message("running")
+            }
+            Some(HealthStatusEnum::HEALTHY) => CheckResult::up().with_message("healthy"),
+            Some(HealthStatusEnum::STARTING) => CheckResult::pending("starting"),
+            Some(status) => CheckResult::down(status.to_string()),
+        };
+        result.with_latency(latency)
+    }
+}
+
+/// Creates an Engine API client for the host. No request is made yet.
+pub fn connect(host: &DockerHost, timeout: Duration) -> Result<Docker, String> {
+    let docker = match host.docker_type.as_str() {
+        DOCKER_TYPE_SOCKET => Docker::connect_with_unix(&host.docker_daemon, timeout.as_secs(), API_DEFAULT_VERSION),
+        DOCKER_TYPE_TCP => Docker::connect_with_http(&host.docker_daemon, timeout.as_secs(), API_DEFAULT_VERSION),
+        other => return Err(format!("Unsupported docker type: {}", other)),
+    };
+    docker.map_err(|e| e.to_string())
+}
+
+/// Number of containers on the host, running or not.
+pub async fn count_containers(docker: &Docker) -> Result<usize, String> {
+    let options = ListContainersOptions::<String> {
+        all: true,
+        ..Default::default()
+    };
+    let containers = docker
+        .list_containers(Some(options))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(containers.len())
+}
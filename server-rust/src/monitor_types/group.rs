@@ -0,0 +1,113 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use crate::models::monitor::{Monitor, STATUS_UP, STATUS_DOWN, STATUS_PENDING};
+use super::{CheckResult, MonitorType};
+
+/// Aggregates the status of the monitors nested under it.
+///
+/// Down if any active child is down, otherwise pending if any is pending
+/// or has not been checked yet, like `server/model/group.js`.
+pub struct GroupMonitorType {
+    pool: SqlitePool,
+}
+
+impl GroupMonitorType {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl MonitorType for GroupMonitorType {
+    fn name(&self) -> &'static str {
+        "group"
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let children = match Monitor::list_children(&self.pool, monitor.id).await {
+            Ok(children) => children,
+            Err(e) => return CheckResult::down(e.to_string()),
+        };
+        if children.is_empty() {
+            return CheckResult::pending("Group empty").with_latency(0);
+        }
+
+        aggregate(&children).with_latency(0)
+    }
+}
+
+/// Worst status among the active children: down, then pending, then up.
+fn aggregate(children: &[Monitor]) -> CheckResult {
+    let mut status = STATUS_UP;
+    for child in children.iter().filter(|child| child.active) {
+        match child.status.as_str() {
+            STATUS_DOWN => status = STATUS_DOWN,
+            STATUS_UP => {}
+            // Pending, or never checked
+            _ if status == STATUS_UP => status = STATUS_PENDING,
+            _ => {}
+        }
+    }
+
+    match status {
+        STATUS_UP => CheckResult::up().with_message("All children up and running"),
+        STATUS_DOWN => CheckResult::down("Child inaccessible"),
+        _ => CheckResult::pending("Child inaccessible"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn child(id: i64, status: &str, active: bool) -> Monitor {
+        Monitor {
+            status: status.to_string(),
+            active,
+            ..Monitor::for_test(id, "http")
+        }
+    }
+
+    #[test]
+    fn all_up() {
+        let result = aggregate(&[child(1, STATUS_UP, true), child(2, STATUS_UP, true)]);
+        assert_eq!(result.status, STATUS_UP);
+        assert_eq!(result.message.as_deref(), Some("All children up and running"));
+    }
+
+    #[test]
+    fn down_wins_over_pending() {
+        let children = [
+            child(1, STATUS_PENDING, true),
+            child(2, STATUS_DOWN, true),
+            child(3, STATUS_UP, true),
+        ];
+        assert_eq!(aggregate(&children).status, STATUS_DOWN);
+
+        let children = [child(1, STATUS_DOWN, true), child(2, STATUS_PENDING, true)];
+        assert_eq!(aggregate(&children).status, STATUS_DOWN);
+    }
+
+    #[test]
+    fn pending_wins_over_up() {
+        let children = [child(1, STATUS_UP, true), child(2, STATUS_PENDING, true)];
+        assert_eq!(aggregate(&children).status, STATUS_PENDING);
+
+        // Never checked counts as pending
+        let children = [child(1, STATUS_UP, true), child(2, "unknown", true)];
+        assert_eq!(aggregate(&children).status, STATUS_PENDING);
+    }
+
+    #[test]
+    fn paused_children_are_ignored() {
+        let children = [
+            child(1, STATUS_UP, true),
+            child(2, STATUS_DOWN, false),
+            child(3, STATUS_PENDING, false),
+        ];
+        assert_eq!(aggregate(&children).status, STATUS_UP);
+
+        // Only paused children left
+        assert_eq!(aggregate(&[child(1, STATUS_DOWN, false)]).status, STATUS_UP);
+    }
+}
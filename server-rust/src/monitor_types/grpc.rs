@@ -0,0 +1,254 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tonic::{
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    codegen::http::uri::PathAndQuery,
+    transport::{Channel, ClientTlsConfig, Endpoint},
+    Status,
+};
+use tonic_health::pb::{health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest};
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config, keyword::snippet};
+
+#[derive(Debug, Deserialize)]
+struct GrpcConfig {
+    #[serde(default)]
+    check_type: GrpcCheckType,
+    #[serde(default)]
+    tls: bool,
+    /// Fully qualified service, e.g. `helloworld.Greeter`. For health checks
+    /// an empty name asks for the status of the whole server.
+    #[serde(default)]
+    service_name: String,
+    method: Option<String>,
+    /// JSON encoded request message.
+    #[serde(default = "default_body")]
+    body: String,
+    /// Base64 encoded `FileDescriptorSet` describing the service, as written
+    /// by `protoc --include_imports --descriptor_set_out`.
+    proto_descriptor: Option<String>,
+    keyword: Option<String>,
+    #[serde(default)]
+    invert_keyword: bool,
+}
+
+fn default_body() -> String {
+    "{}".to_string()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum GrpcCheckType {
+    /// Calls `grpc.health.v1.Health/Check`.
+    #[default]
+    Health,
+    /// Calls a method and looks for a keyword in the JSON response.
+    Keyword,
+}
+
+impl GrpcConfig {
+    fn method(&self) -> Result<MethodDescriptor, String> {
+        let descriptor = self
+            .proto_descriptor
+            .as_deref()
+            .ok_or_else(|| "Proto descriptor is required".to_string())?;
+        let bytes = BASE64
+            .decode(descriptor.trim())
+            .map_err(|e| format!("Invalid proto descriptor: {}", e))?;
+        let pool = DescriptorPool::decode(bytes.as_slice())
+            .map_err(|e| format!("Invalid proto descriptor: {}", e))?;
+
+        let service = pool
+            .get_service_by_name(&self.service_name)
+            .ok_or_else(|| format!("Service {} not found", self.service_name))?;
+        let method = self.method.as_deref().unwrap_or_default();
+        let found = service.methods().find(|m| m.name() == method);
+        found.ok_or_else(|| format!("Method {} not found in {}", method, self.service_name))
+    }
+}
+
+/// Calls a gRPC service, either the standard health check or any unary
+/// method described by a proto descriptor.
+pub struct GrpcMonitorType;
+
+#[async_trait]
+impl MonitorType for GrpcMonitorType {
+    fn name(&self) -> &'static str {
+        "grpc-keyword"
+    }
+
+    fn validate(&self, url: &str, config: &Value) -> Result<(), AppError> {
+        if url.trim().is_empty() {
+            return Err(AppError::BadRequest("URL is required".to_string()));
+        }
+        let config: GrpcConfig = parse_config(config)?;
+        if config.check_type == GrpcCheckType::Keyword {
+            let method = config.method().map_err(AppError::BadRequest)?;
+            if method.is_client_streaming() || method.is_server_streaming() {
+                return Err(AppError::BadRequest("Only unary methods are supported".to_string()));
+            }
+            DynamicMessage::deserialize(
+                method.input(),
+                &mut serde_json::Deserializer::from_str(&config.body),
+            )
+            .map_err(|e| AppError::BadRequest(format!("Invalid request body: {}", e)))?;
+            if config.keyword.as_deref().unwrap_or_default().is_empty() {
+                return Err(AppError::BadRequest("Keyword must not be empty".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: GrpcConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid gRPC config"),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let start_time = Instant::now();
+        let channel = match connect(&monitor.url, config.tls, timeout).await {
+            Ok(channel) => channel,
+            Err(e) => return CheckResult::down(e),
+        };
+
+        let result = match config.check_type {
+            GrpcCheckType::Health => check_health(channel, &config).await,
+            GrpcCheckType::Keyword => check_keyword(channel, &config).await,
+        };
+        result.with_latency(start_time.elapsed().as_millis() as i32)
+    }
+}
+
+async fn connect(url: &str, tls: bool, timeout: Duration) -> Result<Channel, String> {
+    // Node takes the address without a scheme
+    let url = if url.contains("://") {
+        url.to_string()
+    } else if tls {
+        format!("https://{}", url)
+    } else {
+        format!("http://{}", url)
+    };
+
+    let mut endpoint = Endpoint::from_shared(url)
+        .map_err(|e| e.to_string())?
+        .connect_timeout(timeout)
+        .timeout(timeout);
+    if tls {
+        endpoint = endpoint
+            .tls_config(ClientTlsConfig::new())
+            .map_err(|e| e.to_string())?;
+    }
+
+    endpoint.connect().await.map_err(|e| e.to_string())
+}
+
+async fn check_health(channel: Channel, config: &GrpcConfig) -> CheckResult {
+    let request = HealthCheckRequest {
+        service: config.service_name.clone(),
+    };
+    let response = match HealthClient::new(channel).check(request).await {
+        Ok(response) => response.into_inner(),
+        Err(status) => return CheckResult::down(status.message().to_string()),
+    };
+
+    match ServingStatus::try_from(response.status) {
+        Ok(ServingStatus::Serving) => CheckResult::up().with_message("SERVING"),
+        Ok(status) => CheckResult::down(status.as_str_name()),
+        Err(_) => CheckResult::down(format!("Unknown serving status {}", response.status)),
+    }
+}
+
+async fn check_keyword(channel: Channel, config: &GrpcConfig) -> CheckResult {
+    let method = match config.method() {
+        Ok(method) => method,
+        Err(e) => return CheckResult::down(e),
+    };
+    let request = match DynamicMessage::deserialize(
+        method.input(),
+        &mut serde_json::Deserializer::from_str(&config.body),
+    ) {
+        Ok(request) => request,
+        Err(e) => return CheckResult::down(format!("Invalid request body: {}", e)),
+    };
+    let Ok(path) = PathAndQuery::try_from(format!("/{}/{}", method.parent_service().full_name(), method.name())) else {
+        return CheckResult::down("Invalid method");
+    };
+
+    let mut client = tonic::client::Grpc::new(channel);
+    if let Err(e) = client.ready().await {
+        return CheckResult::down(e.to_string());
+    }
+    let codec = DynamicCodec { output: method.output() };
+    let response = match client.unary(tonic::Request::new(request), path, codec).await {
+        Ok(response) => response.into_inner(),
+        Err(status) => return CheckResult::down(status.message().to_string()),
+    };
+    let response = match serde_json::to_string(&response) {
+        Ok(response) => response,
+        Err(e) => return CheckResult::down(e.to_string()),
+    };
+
+    let keyword = config.keyword.as_deref().unwrap_or_default();
+    let found = response.contains(keyword);
+    if found != config.invert_keyword {
+        let is = if found { "is" } else { "not" };
+        CheckResult::up().with_message(format!("{}, keyword [{}] {} found", snippet(&response), keyword, is))
+    } else {
+        let is = if found { "present" } else { "not" };
+        CheckResult::down(format!("but keyword [{}] is {} in [{}]", keyword, is, snippet(&response)))
+    }
+}
+
+/// Encodes and decodes messages whose types are only known at runtime.
+struct DynamicCodec {
+    output: MessageDescriptor,
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder(self.output.clone())
+    }
+}
+
+struct DynamicEncoder;
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+struct DynamicDecoder(MessageDescriptor);
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.0.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
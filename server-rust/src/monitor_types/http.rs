@@ -0,0 +1,557 @@
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    redirect::Policy,
+    Client, Method, Proxy as ReqwestProxy, Response, Url,
+};
+use rustls::{Certificate, ClientConfig, OwnedTrustAnchor, PrivateKey, RootCertStore};
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use crate::{
+    config::secret,
+    models::{monitor::Monitor, proxy::Proxy, tls_info::TlsInfo},
+    monitor_conditions::{operators::NUMBER_OPERATORS, variables::ConditionVariable},
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config, tls::{RecordingVerifier, TlsInspector}};
+
+const CONDITION_VARIABLES: &[ConditionVariable] = &[ConditionVariable::new("status_code", NUMBER_OPERATORS)];
+
+/// How the request is sent, shared by every HTTP-based monitor type.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct HttpRequestConfig {
+    pub method: String,
+    pub headers: HashMap<String, String>,
+    pub body: Option<String>,
+    pub body_encoding: BodyEncoding,
+    pub auth: Option<HttpAuth>,
+    /// Status codes or ranges treated as up, e.g. `["200-299", "401"]`.
+    pub accepted_status_codes: Vec<String>,
+    pub max_redirects: usize,
+    pub ignore_tls: bool,
+}
+
+impl Default for HttpRequestConfig {
+    fn default() -> Self {
+        Self {
+            method: "GET".to_string(),
+            headers: HashMap::new(),
+            body: None,
+            body_encoding: BodyEncoding::Json,
+            auth: None,
+            accepted_status_codes: vec!["200-299".to_string()],
+            max_redirects: 10,
+            ignore_tls: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyEncoding {
+    #[default]
+    Json,
+    Form,
+    Xml,
+}
+
+impl BodyEncoding {
+    fn content_type(self) -> &'static str {
+        match self {
+            BodyEncoding::Json => "application/json",
+            BodyEncoding::Form => "application/x-www-form-urlencoded",
+            BodyEncoding::Xml => "text/xml; charset=utf-8",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HttpAuth {
+    Basic { username: String, password: Option<String> },
+    Bearer { token: String },
+}
+
+impl HttpRequestConfig {
+    pub fn validate(&self) -> Result<(), AppError> {
+        Method::from_bytes(self.method.to_uppercase().as_bytes())
+            .map_err(|_| AppError::BadRequest(format!("Invalid HTTP method: {}", self.method)))?;
+
+        self.header_map()
+            .map_err(AppError::BadRequest)?;
+
+        if self.accepted_status_codes.is_empty() {
+            return Err(AppError::BadRequest("At least one accepted status code is required".to_string()));
+        }
+        for range in &self.accepted_status_codes {
+            parse_status_range(range)
+                .ok_or_else(|| AppError::BadRequest(format!("Invalid status code range: {}", range)))?;
+        }
+
+        if let (Some(body), BodyEncoding::Json) = (&self.body, self.body_encoding) {
+            if !body.trim().is_empty() {
+                serde_json::from_str::<Value>(body)
+                    .map_err(|e| AppError::BadRequest(format!("Your JSON body is invalid. {}", e)))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn header_map(&self) -> Result<HeaderMap, String> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| format!("Invalid header name: {}", name))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| format!("Invalid value for header {}", name))?;
+            headers.insert(name, value);
+        }
+        Ok(headers)
+    }
+
+    fn accepts(&self, status: u16) -> bool {
+        self.accepted_status_codes
+            .iter()
+            .filter_map(|range| parse_status_range(range))
+            .any(|(min, max)| (min..=max).contains(&status))
+    }
+
+    fn client_options(&self, proxy: Option<(i64, String)>, client_tls: ClientTls) -> ClientOptions {
+        ClientOptions {
+            max_redirects: self.max_redirects,
+            ignore_tls: self.ignore_tls,
+            proxy,
+            client_tls,
+        }
+    }
+}
+
+/// Parses "200" or "200-299" into an inclusive range.
+fn parse_status_range(range: &str) -> Option<(u16, u16)> {
+    match range.split_once('-') {
+        Some((min, max)) => {
+            let (min, max) = (min.trim().parse().ok()?, max.trim().parse().ok()?);
+            (min <= max).then_some((min, max))
+        }
+        None => {
+            let code = range.trim().parse().ok()?;
+            Some((code, code))
+        }
+    }
+}
+
+/// Settings that reqwest only allows per client rather than per request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ClientOptions {
+    max_redirects: usize,
+    ignore_tls: bool,
+    /// Proxy id and URL, including credentials.
+    proxy: Option<(i64, String)>,
+    client_tls: ClientTls,
+}
+
+/// Client certificate and extra trusted CAs of a monitor, as PEM.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ClientTls {
+    cert: Option<String>,
+    key: Option<String>,
+    ca: Option<String>,
+}
+
+impl ClientTls {
+    /// The WebPKI roots plus the monitor's CA certificates.
+    fn root_store(&self) -> Result<RootCertStore, String> {
+        let mut roots = RootCertStore::empty();
+        roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+            OwnedTrustAnchor::from_subject_spki_name_constraints(ta.subject, ta.spki, ta.name_constraints)
+        }));
+
+        if let Some(ca) = &self.ca {
+            for pem in split_pem_bundle(ca) {
+                let certificates = rustls_pemfile::certs(&mut pem.as_bytes())
+                    .map_err(|e| format!("Invalid CA certificate: {}", e))?;
+                for der in certificates {
+                    roots
+                        .add(&Certificate(der))
+                        .map_err(|e| format!("Invalid CA certificate: {}", e))?;
+                }
+            }
+        }
+
+        Ok(roots)
+    }
+
+    /// TLS settings verifying with `verifier` and presenting the client
+    /// certificate, if any.
+    fn client_config(&self, verifier: Arc<RecordingVerifier>) -> Result<ClientConfig, String> {
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(verifier);
+
+        match (&self.cert, &self.key) {
+            (Some(cert), Some(key)) => {
+                let certificates = rustls_pemfile::certs(&mut cert.as_bytes())
+                    .map_err(|e| format!("Invalid client certificate: {}", e))?;
+                if certificates.is_empty() {
+                    return Err("Client certificate contains no certificates".to_string());
+                }
+                builder
+                    .with_client_auth_cert(
+                        certificates.into_iter().map(Certificate).collect(),
+                        parse_private_key(key)?,
+                    )
+                    .map_err(|e| format!("Invalid client certificate: {}", e))
+            }
+            (None, None) => Ok(builder.with_no_client_auth()),
+            _ => Err("Client certificate and key must be given together".to_string()),
+        }
+    }
+}
+
+/// Reads the first PKCS#8, PKCS#1 (RSA) or SEC1 (EC) key from a PEM string.
+fn parse_private_key(pem: &str) -> Result<PrivateKey, String> {
+    let items = rustls_pemfile::read_all(&mut pem.as_bytes())
+        .map_err(|e| format!("Invalid client key: {}", e))?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| "Client key contains no private key".to_string())
+}
+
+/// Splits a PEM bundle into its certificates.
+fn split_pem_bundle(bundle: &str) -> Vec<String> {
+    const END: &str = "-----END CERTIFICATE-----";
+    bundle
+        .split_inclusive(END)
+        .map(str::trim)
+        .filter(|pem| pem.ends_with(END))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Validates a monitor's client certificate, key and CA bundle.
+pub fn validate_client_tls(cert: Option<&str>, key: Option<&str>, ca: Option<&str>) -> Result<(), AppError> {
+    let client_tls = ClientTls {
+        cert: cert.map(str::to_string),
+        key: key.map(str::to_string),
+        ca: ca.map(str::to_string),
+    };
+    if ca.is_some_and(|ca| split_pem_bundle(ca).is_empty()) {
+        return Err(AppError::BadRequest("CA bundle contains no certificates".to_string()));
+    }
+    let roots = client_tls.root_store().map_err(AppError::BadRequest)?;
+    client_tls
+        .client_config(Arc::new(RecordingVerifier::new(roots, false)))
+        .map_err(AppError::BadRequest)?;
+    Ok(())
+}
+
+/// A monitor's client, the options it was built with and the verifier that
+/// records the certificates its connections see.
+struct CachedClient {
+    options: ClientOptions,
+    client: Client,
+    verifier: Arc<RecordingVerifier>,
+}
+
+/// Caches one `reqwest::Client` per monitor, so that its connections are
+/// reused between checks. A client is rebuilt when the monitor's settings
+/// change, and dropped when the monitor or its proxy is edited or deleted.
+pub struct HttpClients {
+    pool: SqlitePool,
+    tls: Arc<TlsInspector>,
+    clients: Mutex<HashMap<i64, CachedClient>>,
+}
+
+impl HttpClients {
+    pub fn new(pool: SqlitePool, tls: Arc<TlsInspector>) -> Self {
+        Self {
+            pool,
+            tls,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Certificate chain presented to the monitor's client by the host of
+    /// its URL, if it connected over TLS.
+    pub fn tls_info(&self, monitor: &Monitor) -> Option<TlsInfo> {
+        let url = Url::parse(&monitor.url).ok().filter(|url| url.scheme() == "https")?;
+        let host = url.host_str()?.trim_start_matches('[').trim_end_matches(']');
+
+        let verifier = self.clients.lock().unwrap().get(&monitor.id)?.verifier.clone();
+        match verifier.tls_info(host)? {
+            Ok(info) => Some(info),
+            Err(e) => {
+                tracing::warn!("Cannot read TLS info for monitor #{}: {}", monitor.id, e);
+                None
+            }
+        }
+    }
+
+    /// Records the certificate chain seen by the monitor's last request and
+    /// sends any due expiry notifications.
+    async fn inspect_tls(&self, monitor: &Monitor) {
+        if let Some(info) = self.tls_info(monitor) {
+            self.tls.inspect(monitor, &info).await;
+        }
+    }
+
+    /// Client for the monitor's request settings, going through its proxy
+    /// and presenting its client certificate.
+    async fn for_monitor(&self, monitor: &Monitor, request: &HttpRequestConfig) -> Result<Client, String> {
+        let proxy = match monitor.proxy_id {
+            Some(id) => {
+                let proxy = Proxy::find(&self.pool, id)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| "Proxy not found".to_string())?;
+                Some((proxy.id, proxy.url()?))
+            }
+            None => None,
+        };
+        let client_tls = ClientTls {
+            cert: monitor.tls_cert.clone(),
+            key: monitor.tls_key.as_deref().map(secret::decrypt).transpose()?,
+            ca: monitor.tls_ca.clone(),
+        };
+        self.get(monitor.id, request.client_options(proxy, client_tls))
+    }
+
+    /// Drops the cached client of a monitor.
+    pub fn remove(&self, monitor_id: i64) {
+        self.clients.lock().unwrap().remove(&monitor_id);
+    }
+
+    /// Drops the cached clients of every monitor using the proxy.
+    pub fn remove_proxy(&self, proxy_id: i64) {
+        self.clients
+            .lock()
+            .unwrap()
+            .retain(|_, cached| cached.options.proxy.as_ref().map(|(id, _)| *id) != Some(proxy_id));
+    }
+
+    fn get(&self, monitor_id: i64, options: ClientOptions) -> Result<Client, String> {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(cached) = clients.get(&monitor_id).filter(|cached| cached.options == options) {
+            return Ok(cached.client.clone());
+        }
+
+        // With no redirects allowed the 3xx response itself is checked
+        // against the accepted status codes
+        let redirect = match options.max_redirects {
+            0 => Policy::none(),
+            max => Policy::limited(max),
+        };
+        let verifier = Arc::new(RecordingVerifier::new(options.client_tls.root_store()?, options.ignore_tls));
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .redirect(redirect)
+            .use_preconfigured_tls(options.client_tls.client_config(verifier.clone())?);
+        if let Some((_, proxy)) = &options.proxy {
+            builder = builder.proxy(ReqwestProxy::all(proxy).map_err(|e| e.to_string())?);
+        }
+        let client = builder.build().map_err(|e| e.to_string())?;
+        clients.insert(monitor_id, CachedClient { options, client: client.clone(), verifier });
+        Ok(client)
+    }
+}
+
+/// Plain HTTP(S) check: the monitor is up when the response status is one of
+/// the accepted status codes.
+pub struct HttpMonitorType {
+    name: &'static str,
+    clients: Arc<HttpClients>,
+}
+
+impl HttpMonitorType {
+    pub fn new(name: &'static str, clients: Arc<HttpClients>) -> Self {
+        Self { name, clients }
+    }
+}
+
+#[async_trait]
+impl MonitorType for HttpMonitorType {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn validate(&self, url: &str, config: &Value) -> Result<(), AppError> {
+        validate_url(url)?;
+        parse_config::<HttpRequestConfig>(config)?.validate()
+    }
+
+    fn condition_variables(&self) -> &'static [ConditionVariable] {
+        CONDITION_VARIABLES
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let request: HttpRequestConfig = match parse_config(&monitor.config) {
+            Ok(request) => request,
+            Err(_) => return CheckResult::down("Invalid HTTP config"),
+        };
+
+        let response = match send_request(&self.clients, monitor, &request).await {
+            Ok(response) => response,
+            Err(message) => return CheckResult::down(message),
+        };
+        let result = CheckResult::up()
+            .with_message(response.status().to_string())
+            .with_variable("status_code", response.status().as_u16());
+
+        match self.clients.tls_info(monitor) {
+            Some(info) => result.with_details(serde_json::json!({
+                "tls_valid": info.valid,
+                "tls_days_remaining": info.leaf().map(|cert| cert.days_remaining),
+            })),
+            None => result,
+        }
+    }
+}
+
+/// Sends the monitor's HTTP request, failing on transport errors and status
+/// codes that are not accepted. For HTTPS URLs the certificate chain of the
+/// connection is recorded as well, even when the handshake failed.
+///
+/// Shared by every HTTP-based monitor type.
+pub async fn send_request(
+    clients: &HttpClients,
+    monitor: &Monitor,
+    request: &HttpRequestConfig,
+) -> Result<Response, String> {
+    let client = clients.for_monitor(monitor, request).await?;
+    let method = Method::from_bytes(request.method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", request.method))?;
+
+    let mut builder = client
+        .request(method, &monitor.url)
+        .timeout(Duration::from_secs(monitor.timeout.max(1) as u64))
+        .header(
+            reqwest::header::ACCEPT,
+            "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8",
+        );
+
+    if let Some(body) = request.body.as_ref().filter(|body| !body.trim().is_empty()) {
+        builder = builder
+            .header(reqwest::header::CONTENT_TYPE, request.body_encoding.content_type())
+            .body(body.clone());
+    }
+
+    builder = match &request.auth {
+        Some(HttpAuth::Basic { username, password }) => builder.basic_auth(username, password.as_ref()),
+        Some(HttpAuth::Bearer { token }) => builder.bearer_auth(token),
+        None => builder,
+    };
+
+    // Custom headers replace the defaults above
+    builder = builder.headers(request.header_map()?);
+
+    let response = builder.send().await;
+    // Record the chain before bailing out: a handshake rejected for an
+    // expired or untrusted certificate is what the user most needs to hear about
+    clients.inspect_tls(monitor).await;
+    let response = response.map_err(|e| e.to_string())?;
+
+    if !request.accepts(response.status().as_u16()) {
+        return Err(format!("HTTP status code: {}", response.status()));
+    }
+
+    Ok(response)
+}
+
+/// Validates that the URL is an HTTP(S) URL.
+pub fn validate_url(url: &str) -> Result<(), AppError> {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(AppError::BadRequest("URL must start with http:// or https://".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::notification::NotificationService;
+    use serde_json::json;
+    use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+    fn config(value: Value) -> HttpRequestConfig {
+        parse_config(&value).unwrap()
+    }
+
+    #[test]
+    fn status_ranges() {
+        assert_eq!(parse_status_range("200-299"), Some((200, 299)));
+        assert_eq!(parse_status_range("401"), Some((401, 401)));
+        assert_eq!(parse_status_range(" 200 - 299 "), Some((200, 299)));
+        assert_eq!(parse_status_range("299-200"), None);
+        assert_eq!(parse_status_range("2xx"), None);
+        assert_eq!(parse_status_range("200-"), None);
+        assert_eq!(parse_status_range(""), None);
+    }
+
+    #[test]
+    fn accepted_status_codes() {
+        let config = config(json!({ "accepted_status_codes": ["200-299", "401"] }));
+        for status in [200, 204, 299, 401] {
+            assert!(config.accepts(status), "{}", status);
+        }
+        for status in [199, 300, 400, 404, 500] {
+            assert!(!config.accepts(status), "{}", status);
+        }
+        assert!(HttpRequestConfig::default().accepts(200));
+    }
+
+    #[test]
+    fn validate_status_codes() {
+        assert!(config(json!({ "accepted_status_codes": ["200-299", " 300 - 399 "] })).validate().is_ok());
+        assert!(config(json!({ "accepted_status_codes": ["299-200"] })).validate().is_err());
+        assert!(config(json!({ "accepted_status_codes": ["ok"] })).validate().is_err());
+        assert!(config(json!({ "accepted_status_codes": [] })).validate().is_err());
+        assert!(config(json!({ "method": "GET POST" })).validate().is_err());
+        assert!(config(json!({ "body": "{not json" })).validate().is_err());
+    }
+
+    fn clients() -> HttpClients {
+        let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        let notifications = Arc::new(NotificationService::new(pool.clone()));
+        HttpClients::new(pool.clone(), Arc::new(TlsInspector::new(pool, notifications)))
+    }
+
+    #[tokio::test]
+    async fn redirects_are_checked_when_not_followed() {
+        let server = MockServer::start().await;
+        Mock::given(path("/old"))
+            .respond_with(ResponseTemplate::new(302).insert_header("Location", "/new"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/new")).respond_with(ResponseTemplate::new(200)).mount(&server).await;
+
+        let clients = clients();
+        let mut monitor = Monitor::for_test(1, "http");
+        monitor.url = format!("{}/old", server.uri());
+
+        let not_followed = config(json!({ "max_redirects": 0, "accepted_status_codes": ["300-399"] }));
+        let response = send_request(&clients, &monitor, &not_followed).await.unwrap();
+        assert_eq!(response.status().as_u16(), 302);
+
+        let rejected = config(json!({ "max_redirects": 0 }));
+        assert_eq!(
+            send_request(&clients, &monitor, &rejected).await.unwrap_err(),
+            "HTTP status code: 302 Found"
+        );
+
+        let followed = config(json!({}));
+        let response = send_request(&clients, &monitor, &followed).await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+    }
+}
@@ -0,0 +1,283 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{
+    CheckResult, MonitorType, parse_config,
+    http::{HttpClients, HttpRequestConfig, send_request, validate_url},
+};
+
+pub const OPERATORS: &[&str] = &["==", "!=", "<", "<=", ">", ">=", "contains"];
+
+#[derive(Debug, Deserialize)]
+struct JsonQueryConfig {
+    #[serde(flatten)]
+    request: HttpRequestConfig,
+    /// Path into the response, e.g. `db` or `$.checks[0].status`.
+    json_path: String,
+    #[serde(default = "default_operator")]
+    json_path_operator: String,
+    expected_value: String,
+}
+
+fn default_operator() -> String {
+    "==".to_string()
+}
+
+/// HTTP check that queries a field of a JSON response and compares it with an
+/// expected value.
+pub struct JsonQueryMonitorType {
+    clients: Arc<HttpClients>,
+}
+
+impl JsonQueryMonitorType {
+    pub fn new(clients: Arc<HttpClients>) -> Self {
+        Self { clients }
+    }
+}
+
+#[async_trait]
+impl MonitorType for JsonQueryMonitorType {
+    fn name(&self) -> &'static str {
+        "json-query"
+    }
+
+    fn validate(&self, url: &str, config: &Value) -> Result<(), AppError> {
+        validate_url(url)?;
+        let config: JsonQueryConfig = parse_config(config)?;
+        config.request.validate()?;
+        validate_query(&config.json_path, &config.json_path_operator)
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: JsonQueryConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid JSON query config"),
+        };
+
+        let response = match send_request(&self.clients, monitor, &config.request).await {
+            Ok(response) => response,
+            Err(message) => return CheckResult::down(message),
+        };
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(e) => return CheckResult::down(e.to_string()),
+        };
+
+        let data = serde_json::from_str(&body).unwrap_or(Value::String(body));
+        match evaluate_json_query(&data, &config.json_path, &config.json_path_operator, &config.expected_value) {
+            Ok((true, value)) => CheckResult::up().with_message(format!(
+                "JSON query passes (comparing {} {} {})",
+                value, config.json_path_operator, config.expected_value
+            )),
+            Ok((false, value)) => CheckResult::down(format!(
+                "JSON query does not pass (comparing {} {} {})",
+                value, config.json_path_operator, config.expected_value
+            )),
+            Err(message) => CheckResult::down(message),
+        }
+    }
+}
+
+/// Validates a query path and comparison operator.
+pub fn validate_query(path: &str, operator: &str) -> Result<(), AppError> {
+    if !OPERATORS.contains(&operator) {
+        return Err(AppError::BadRequest(format!("Invalid JSON query operator: {}", operator)));
+    }
+    parse_path(path)
+        .map(|_| ())
+        .ok_or_else(|| AppError::BadRequest(format!("Invalid JSON query: {}", path)))
+}
+
+/// Looks up `path` in `data` and compares the result with `expected`.
+///
+/// Returns whether the comparison passed, along with the value found.
+pub fn evaluate_json_query(
+    data: &Value,
+    path: &str,
+    operator: &str,
+    expected: &str,
+) -> Result<(bool, String), String> {
+    let segments = parse_path(path).ok_or_else(|| format!("Invalid JSON query: {}", path))?;
+
+    let mut current = data;
+    for segment in &segments {
+        let next = match segment {
+            Segment::Key(key) => current.get(key.as_str()),
+            Segment::Index(index) => current.get(*index),
+        };
+        current = next.ok_or_else(|| {
+            format!(
+                "Error evaluating JSON query: Empty or undefined response. Response from server was: {}",
+                truncate(&data.to_string())
+            )
+        })?;
+    }
+
+    let value = match current {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => {
+            return Err("Error evaluating JSON query: Empty or undefined response".to_string());
+        }
+        Value::Array(_) | Value::Object(_) => {
+            return Err(format!(
+                "Error evaluating JSON query: The evaluated value {} cannot be compared to the expected value",
+                truncate(&current.to_string())
+            ));
+        }
+    };
+
+    Ok((compare(&value, operator, expected)?, value))
+}
+
+/// Compares two values as numbers for ordering operators and as strings
+/// otherwise.
+pub fn compare(value: &str, operator: &str, expected: &str) -> Result<bool, String> {
+    Ok(match operator {
+        "==" => value == expected,
+        "!=" => value != expected,
+        "contains" => value.contains(expected),
+        "<" | "<=" | ">" | ">=" => {
+            let parse = |s: &str| {
+                s.trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("Cannot compare non-numeric value {} with {}", s, operator))
+            };
+            let (a, b) = (parse(value)?, parse(expected)?);
+            match operator {
+                "<" => a < b,
+                "<=" => a <= b,
+                ">" => a > b,
+                _ => a >= b,
+            }
+        }
+        _ => return Err(format!("Invalid condition {}", operator)),
+    })
+}
+
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parses a dotted path such as `$.checks[0].status` into segments.
+fn parse_path(path: &str) -> Option<Vec<Segment>> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let path = path.strip_prefix('.').unwrap_or(path);
+
+    let mut segments = Vec::new();
+    if path.is_empty() {
+        return Some(segments);
+    }
+
+    for part in path.split('.') {
+        let (key, mut rest) = match part.find('[') {
+            Some(i) => (&part[..i], &part[i..]),
+            None => (part, ""),
+        };
+        if key.is_empty() && rest.is_empty() {
+            return None;
+        }
+        if !key.is_empty() {
+            segments.push(Segment::Key(key.to_string()));
+        }
+        while !rest.is_empty() {
+            let end = rest.find(']')?;
+            let index = rest.get(1..end)?.trim().parse().ok()?;
+            segments.push(Segment::Index(index));
+            rest = &rest[end + 1..];
+            if !rest.is_empty() && !rest.starts_with('[') {
+                return None;
+            }
+        }
+    }
+
+    Some(segments)
+}
+
+fn truncate(s: &str) -> String {
+    if s.chars().count() > 100 {
+        format!("{}… (truncated)", s.chars().take(100).collect::<String>())
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn query(data: &Value, path: &str, operator: &str, expected: &str) -> Result<(bool, String), String> {
+        evaluate_json_query(data, path, operator, expected)
+    }
+
+    #[test]
+    fn dotted_path_with_index() {
+        let data = json!({ "checks": [{ "status": "ok" }, { "status": "failed" }] });
+        assert_eq!(query(&data, "$.checks[0].status", "==", "ok"), Ok((true, "ok".to_string())));
+        assert_eq!(query(&data, "checks[1].status", "==", "ok"), Ok((false, "failed".to_string())));
+        assert!(query(&data, "$.checks[2].status", "==", "ok").unwrap_err().contains("undefined"));
+    }
+
+    #[test]
+    fn nested_indexes() {
+        let data = json!({ "a": [[1, 2], [3, 4]] });
+        assert_eq!(query(&data, "a[0][1]", "==", "2"), Ok((true, "2".to_string())));
+        assert_eq!(query(&data, "$.a[1][0]", ">", "2"), Ok((true, "3".to_string())));
+    }
+
+    #[test]
+    fn empty_path_is_the_whole_document() {
+        assert_eq!(query(&json!("healthy"), "", "==", "healthy"), Ok((true, "healthy".to_string())));
+        assert_eq!(query(&json!(42), "$", ">=", "42"), Ok((true, "42".to_string())));
+        assert!(validate_query("", "==").is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_paths() {
+        for path in ["a..b", "a[x]", "a[0]b", "a[0", "a[-1]"] {
+            assert!(validate_query(path, "==").is_err(), "{}", path);
+            assert!(query(&json!({}), path, "==", "1").unwrap_err().starts_with("Invalid JSON query"));
+        }
+        assert!(validate_query("a", "=~").is_err());
+    }
+
+    #[test]
+    fn compares_values() {
+        assert_eq!(compare("10", "<", "9.5"), Ok(false));
+        assert_eq!(compare(" 10 ", ">", "9.5"), Ok(true));
+        assert_eq!(compare("10", "<=", "10"), Ok(true));
+        // String comparison for equality
+        assert_eq!(compare("10", "==", "10.0"), Ok(false));
+        assert_eq!(compare("all systems go", "contains", "go"), Ok(true));
+        assert_eq!(compare("a", "!=", "b"), Ok(true));
+        assert!(compare("1", "~", "1").is_err());
+    }
+
+    #[test]
+    fn numeric_operators_need_numbers() {
+        assert_eq!(
+            compare("ok", ">", "1"),
+            Err("Cannot compare non-numeric value ok with >".to_string())
+        );
+        assert!(compare("1", "<", "many").is_err());
+        assert!(query(&json!({ "a": "ok" }), "a", ">=", "1").is_err());
+    }
+
+    #[test]
+    fn non_scalar_results() {
+        let data = json!({ "none": null, "list": [1, 2], "object": { "a": 1 }, "flag": true });
+        assert!(query(&data, "none", "==", "null").unwrap_err().contains("Empty or undefined"));
+        assert!(query(&data, "list", "==", "[1,2]").unwrap_err().contains("cannot be compared"));
+        assert!(query(&data, "object", "==", "{}").unwrap_err().contains("cannot be compared"));
+        assert_eq!(query(&data, "flag", "==", "true"), Ok((true, "true".to_string())));
+    }
+}
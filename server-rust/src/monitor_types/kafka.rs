@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use rdkafka::{
+    config::ClientConfig,
+    producer::{FutureProducer, FutureRecord},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config};
+
+#[derive(Debug, Deserialize)]
+struct KafkaConfig {
+    /// Bootstrap brokers as `host:port`.
+    brokers: Vec<String>,
+    topic: String,
+    #[serde(default = "default_message")]
+    message: String,
+    #[serde(default)]
+    ssl: bool,
+    #[serde(default)]
+    allow_auto_topic_creation: bool,
+    sasl: Option<SaslConfig>,
+}
+
+fn default_message() -> String {
+    "{}".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+struct SaslConfig {
+    #[serde(default)]
+    mechanism: SaslMechanism,
+    #[serde(default)]
+    username: String,
+    #[serde(default)]
+    password: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum SaslMechanism {
+    #[default]
+    None,
+    Plain,
+    ScramSha256,
+    ScramSha512,
+}
+
+impl SaslMechanism {
+    /// Name of the mechanism in librdkafka's `sasl.mechanism`.
+    fn as_str(&self) -> Option<&'static str> {
+        match self {
+            SaslMechanism::None => None,
+            SaslMechanism::Plain => Some("PLAIN"),
+            SaslMechanism::ScramSha256 => Some("SCRAM-SHA-256"),
+            SaslMechanism::ScramSha512 => Some("SCRAM-SHA-512"),
+        }
+    }
+}
+
+impl KafkaConfig {
+    fn sasl_mechanism(&self) -> Option<&'static str> {
+        self.sasl.as_ref().and_then(|sasl| sasl.mechanism.as_str())
+    }
+
+    fn client_config(&self, timeout: Duration) -> ClientConfig {
+        let security_protocol = match (self.ssl, self.sasl_mechanism().is_some()) {
+            (false, false) => "plaintext",
+            (true, false) => "ssl",
+            (false, true) => "sasl_plaintext",
+            (true, true) => "sasl_ssl",
+        };
+
+        let mut client_config = ClientConfig::new();
+        client_config
+            .set("bootstrap.servers", self.brokers.join(","))
+            .set("security.protocol", security_protocol)
+            .set("allow.auto.create.topics", self.allow_auto_topic_creation.to_string())
+            .set("message.timeout.ms", timeout.as_millis().to_string())
+            .set("socket.timeout.ms", timeout.as_millis().max(10).to_string());
+
+        if let (Some(sasl), Some(mechanism)) = (&self.sasl, self.sasl_mechanism()) {
+            client_config
+                .set("sasl.mechanism", mechanism)
+                .set("sasl.username", &sasl.username)
+                .set("sasl.password", &sasl.password);
+        }
+
+        client_config
+    }
+}
+
+/// Produces a test message to a Kafka topic.
+///
+/// Only built with the `kafka` cargo feature.
+pub struct KafkaProducerMonitorType;
+
+#[async_trait]
+impl MonitorType for KafkaProducerMonitorType {
+    fn name(&self) -> &'static str {
+        "kafka-producer"
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: KafkaConfig = parse_config(config)?;
+        if config.brokers.iter().all(|broker| broker.trim().is_empty()) {
+            return Err(AppError::BadRequest("At least one broker is required".to_string()));
+        }
+        if config.topic.trim().is_empty() {
+            return Err(AppError::BadRequest("Topic is required".to_string()));
+        }
+        if let Some(sasl) = &config.sasl {
+            if sasl.mechanism != SaslMechanism::None && sasl.username.is_empty() {
+                return Err(AppError::BadRequest("SASL username is required".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: KafkaConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid Kafka config"),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let producer: FutureProducer = match config.client_config(timeout).create() {
+            Ok(producer) => producer,
+            Err(e) => return CheckResult::down(e.to_string()),
+        };
+
+        let record = FutureRecord::<(), _>::to(&config.topic).payload(&config.message);
+        let start_time = Instant::now();
+        let (partition, offset) = match tokio::time::timeout(timeout, producer.send(record, timeout)).await {
+            Ok(Ok(delivery)) => delivery,
+            Ok(Err((e, _))) => return CheckResult::down(format!("Error sending message: {}", e)),
+            Err(_) => return CheckResult::down(format!("Message not delivered after {}s", timeout.as_secs())),
+        };
+        let latency = start_time.elapsed().as_millis() as i32;
+
+        CheckResult::up()
+            .with_message("Message sent successfully")
+            .with_latency(latency)
+            .with_details(json!({ "partition": partition, "offset": offset }))
+    }
+}
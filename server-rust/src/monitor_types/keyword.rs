@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{
+    CheckResult, MonitorType, parse_config,
+    http::{HttpClients, HttpRequestConfig, send_request, validate_url},
+};
+
+/// Longest body snippet included in a heartbeat message.
+const SNIPPET_LENGTH: usize = 50;
+
+#[derive(Debug, Deserialize)]
+struct KeywordConfig {
+    #[serde(flatten)]
+    request: HttpRequestConfig,
+    keyword: String,
+    /// Up when the keyword is *not* found.
+    #[serde(default)]
+    invert_keyword: bool,
+    #[serde(default)]
+    case_insensitive: bool,
+}
+
+/// HTTP check that also requires a keyword to be present in the response body.
+pub struct KeywordMonitorType {
+    clients: Arc<HttpClients>,
+}
+
+impl KeywordMonitorType {
+    pub fn new(clients: Arc<HttpClients>) -> Self {
+        Self { clients }
+    }
+}
+
+#[async_trait]
+impl MonitorType for KeywordMonitorType {
+    fn name(&self) -> &'static str {
+        "keyword"
+    }
+
+    fn validate(&self, url: &str, config: &Value) -> Result<(), AppError> {
+        validate_url(url)?;
+        let config: KeywordConfig = parse_config(config)?;
+        config.request.validate()?;
+        if config.keyword.is_empty() {
+            return Err(AppError::BadRequest("Keyword must not be empty".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: KeywordConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid keyword config"),
+        };
+
+        let response = match send_request(&self.clients, monitor, &config.request).await {
+            Ok(response) => response,
+            Err(message) => return CheckResult::down(message),
+        };
+        let status = response.status();
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(e) => return CheckResult::down(e.to_string()),
+        };
+
+        let found = if config.case_insensitive {
+            body.to_lowercase().contains(&config.keyword.to_lowercase())
+        } else {
+            body.contains(&config.keyword)
+        };
+
+        if found != config.invert_keyword {
+            let not = if config.invert_keyword { "not " } else { "" };
+            CheckResult::up().with_message(format!("{}, keyword {}found", status, not))
+        } else {
+            let not = if config.invert_keyword { "" } else { "not " };
+            CheckResult::down(format!(
+                "{}, but keyword is {}in [{}]",
+                status,
+                not,
+                snippet(&body)
+            ))
+        }
+    }
+}
+
+/// Collapses a response body into a short, single-line snippet without markup.
+pub fn snippet(body: &str) -> String {
+    let mut text = String::with_capacity(body.len());
+    let mut in_tag = false;
+    for c in body.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if in_tag => {}
+            _ => text.push(c),
+        }
+    }
+
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > SNIPPET_LENGTH {
+        let truncated: String = text.chars().take(SNIPPET_LENGTH - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        text
+    }
+}
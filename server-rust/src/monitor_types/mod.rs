@@ -0,0 +1,195 @@
+// Monitor types module
+pub mod database;
+pub mod dns;
+pub mod docker;
+pub mod group;
+pub mod grpc;
+pub mod http;
+pub mod json_query;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod keyword;
+pub mod mongodb;
+pub mod mqtt;
+pub mod ping;
+pub mod port;
+pub mod push;
+pub mod rabbitmq;
+pub mod radius;
+pub mod redis;
+pub mod snmp;
+pub mod steam;
+pub mod tls;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use crate::{
+    models::monitor::{Monitor, STATUS_UP, STATUS_DOWN, STATUS_PENDING},
+    monitor_conditions::variables::ConditionVariable,
+    error::AppError,
+};
+
+/// Outcome of a single check, shared by every monitor type.
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub status: &'static str,
+    /// Latency in milliseconds. When `None` the total check time is used.
+    pub latency: Option<i32>,
+    pub message: Option<String>,
+    /// Type-specific data recorded alongside the heartbeat.
+    pub details: Option<Value>,
+    /// Nothing to record this time, the monitor keeps its current status.
+    pub skipped: bool,
+    /// Seconds until the monitor should be checked again, overriding its interval.
+    pub next_check: Option<i32>,
+    /// Values the monitor's conditions are evaluated against.
+    pub variables: Map<String, Value>,
+}
+
+impl CheckResult {
+    pub fn up() -> Self {
+        Self {
+            status: STATUS_UP,
+            latency: None,
+            message: None,
+            details: None,
+            skipped: false,
+            next_check: None,
+            variables: Map::new(),
+        }
+    }
+
+    pub fn down(message: impl Into<String>) -> Self {
+        Self {
+            status: STATUS_DOWN,
+            latency: None,
+            message: Some(message.into()),
+            details: None,
+            skipped: false,
+            next_check: None,
+            variables: Map::new(),
+        }
+    }
+
+    /// Neither up nor down yet, e.g. a group whose children were not checked.
+    pub fn pending(message: impl Into<String>) -> Self {
+        Self {
+            status: STATUS_PENDING,
+            ..Self::down(message)
+        }
+    }
+
+    pub fn skipped() -> Self {
+        Self {
+            skipped: true,
+            ..Self::up()
+        }
+    }
+
+    pub fn with_latency(mut self, latency: i32) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn with_next_check(mut self, seconds: i32) -> Self {
+        self.next_check = Some(seconds);
+        self
+    }
+
+    pub fn with_variable(mut self, id: &str, value: impl Into<Value>) -> Self {
+        self.variables.insert(id.to_string(), value.into());
+        self
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.status == STATUS_UP
+    }
+}
+
+/// A kind of check a monitor can run, e.g. "http" or "ping".
+///
+/// Type-specific settings live in the monitor's `config` JSON object.
+#[async_trait]
+pub trait MonitorType: Send + Sync {
+    /// Name stored in the monitor's `type` column.
+    fn name(&self) -> &'static str;
+
+    /// Validates the monitor settings before a monitor is created or updated.
+    fn validate(&self, _url: &str, _config: &Value) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    /// Variables this type sets on its results for use in conditions.
+    fn condition_variables(&self) -> &'static [ConditionVariable] {
+        &[]
+    }
+
+    /// Runs the check against the monitor.
+    async fn check(&self, monitor: &Monitor) -> CheckResult;
+}
+
+/// Lookup table from type name to implementation.
+#[derive(Default)]
+pub struct MonitorTypeRegistry {
+    types: HashMap<&'static str, Arc<dyn MonitorType>>,
+}
+
+impl MonitorTypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, monitor_type: impl MonitorType + 'static) {
+        self.types.insert(monitor_type.name(), Arc::new(monitor_type));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn MonitorType>> {
+        self.types.get(name).cloned()
+    }
+
+    /// Returns the type, or `BadRequest` if no such type is registered.
+    pub fn require(&self, name: &str) -> Result<Arc<dyn MonitorType>, AppError> {
+        self.get(name)
+            .ok_or_else(|| AppError::BadRequest(format!("Unsupported monitor type: {}", name)))
+    }
+}
+
+/// Deserializes a monitor's `config` into a type-specific settings struct.
+pub fn parse_config<T: DeserializeOwned>(config: &Value) -> Result<T, AppError> {
+    serde_json::from_value(config.clone())
+        .map_err(|e| AppError::BadRequest(format!("Invalid monitor config: {}", e)))
+}
+
+/// Opens a UDP socket connected to `host:port`, bound to the address family
+/// of the resolved target so IPv6-only hosts work too.
+pub async fn connect_udp(host: &str, port: u16) -> Result<UdpSocket, String> {
+    let target = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("Cannot resolve {}", host))?;
+    let local: SocketAddr = if target.is_ipv6() {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    };
+
+    let socket = UdpSocket::bind(local).await.map_err(|e| e.to_string())?;
+    socket.connect(target).await.map_err(|e| e.to_string())?;
+    Ok(socket)
+}
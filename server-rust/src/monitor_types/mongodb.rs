@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use mongodb::{
+    bson::{self, Bson, Document},
+    options::ClientOptions,
+    Client,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config, json_query};
+
+#[derive(Debug, Deserialize)]
+struct MongoDbConfig {
+    connection_string: String,
+    /// Database command as JSON, `{"ping": 1}` when not set.
+    command: Option<String>,
+    /// Query into the command result.
+    json_path: Option<String>,
+    #[serde(default = "default_operator")]
+    json_path_operator: String,
+    expected_value: Option<String>,
+}
+
+fn default_operator() -> String {
+    "==".to_string()
+}
+
+impl MongoDbConfig {
+    fn command(&self) -> Result<Document, String> {
+        let Some(command) = self.command.as_deref().filter(|c| !c.trim().is_empty()) else {
+            return Ok(bson::doc! { "ping": 1 });
+        };
+        let value: Value = serde_json::from_str(command).map_err(|e| format!("Invalid command: {}", e))?;
+        bson::to_document(&value).map_err(|e| format!("Invalid command: {}", e))
+    }
+}
+
+/// Runs a command against a MongoDB database, optionally comparing a field of
+/// the result with an expected value.
+pub struct MongoDbMonitorType;
+
+#[async_trait]
+impl MonitorType for MongoDbMonitorType {
+    fn name(&self) -> &'static str {
+        "mongodb"
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: MongoDbConfig = parse_config(config)?;
+        if !["mongodb://", "mongodb+srv://"]
+            .iter()
+            .any(|scheme| config.connection_string.starts_with(scheme))
+        {
+            return Err(AppError::BadRequest("Invalid MongoDB connection string".to_string()));
+        }
+        config.command().map_err(AppError::BadRequest)?;
+        if let Some(path) = &config.json_path {
+            json_query::validate_query(path, &config.json_path_operator)?;
+            if config.expected_value.is_none() {
+                return Err(AppError::BadRequest("Expected value is required".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: MongoDbConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid MongoDB config"),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let start_time = Instant::now();
+        let result = match tokio::time::timeout(timeout, run_command(&config, timeout)).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => return CheckResult::down(e),
+            Err(_) => return CheckResult::down(format!("Command timed out after {}s", timeout.as_secs())),
+        };
+        let latency = start_time.elapsed().as_millis() as i32;
+
+        if result.get("ok").and_then(Value::as_f64) != Some(1.0) {
+            return CheckResult::down("MongoDB command failed").with_details(result);
+        }
+
+        let result = match (&config.json_path, &config.expected_value) {
+            (Some(path), Some(expected)) => {
+                match json_query::evaluate_json_query(&result, path, &config.json_path_operator, expected) {
+                    Ok((true, _)) => CheckResult::up()
+                        .with_message("Command executed successfully and expected value was found"),
+                    Ok((false, value)) => CheckResult::down(format!(
+                        "Command executed, but value is not equal to expected value, value was: [{}]",
+                        value
+                    )),
+                    Err(e) => CheckResult::down(e),
+                }
+            }
+            _ => CheckResult::up().with_message("Command executed successfully"),
+        };
+
+        result.with_latency(latency)
+    }
+}
+
+/// Runs the command on the database named in the connection string, or
+/// `admin` when it names none. Returns the result as relaxed extended JSON.
+async fn run_command(config: &MongoDbConfig, timeout: Duration) -> Result<Value, String> {
+    let command = config.command()?;
+
+    let mut options = ClientOptions::parse(&config.connection_string)
+        .await
+        .map_err(|e| e.to_string())?;
+    options.connect_timeout = Some(timeout);
+    options.server_selection_timeout = Some(timeout);
+    let client = Client::with_options(options).map_err(|e| e.to_string())?;
+
+    let database = client
+        .default_database()
+        .unwrap_or_else(|| client.database("admin"));
+    let result = database.run_command(command, None).await.map_err(|e| e.to_string())?;
+
+    Ok(Bson::Document(result).into_relaxed_extjson())
+}
@@ -0,0 +1,288 @@
+use async_trait::async_trait;
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS, Transport};
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config, json_query};
+
+#[derive(Debug, Deserialize)]
+struct MqttConfig {
+    /// Broker host, optionally prefixed with `mqtt://` or `mqtts://`.
+    hostname: String,
+    #[serde(default = "default_port")]
+    port: u16,
+    topic: String,
+    username: Option<String>,
+    password: Option<String>,
+    #[serde(default)]
+    tls: bool,
+    #[serde(default)]
+    check_type: MqttCheckType,
+    /// Keyword the message must contain (keyword check).
+    success_message: Option<String>,
+    /// Query into the JSON message (json-query check).
+    json_path: Option<String>,
+    #[serde(default = "default_operator")]
+    json_path_operator: String,
+    expected_value: Option<String>,
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+fn default_operator() -> String {
+    "==".to_string()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum MqttCheckType {
+    #[default]
+    Keyword,
+    JsonQuery,
+}
+
+impl MqttConfig {
+    /// Host without scheme, and whether TLS is required.
+    fn broker(&self) -> (&str, bool) {
+        if let Some(host) = self.hostname.strip_prefix("mqtts://") {
+            (host, true)
+        } else {
+            (self.hostname.strip_prefix("mqtt://").unwrap_or(&self.hostname), self.tls)
+        }
+    }
+}
+
+/// Subscribes to a topic on an MQTT broker and waits for a message.
+pub struct MqttMonitorType;
+
+#[async_trait]
+impl MonitorType for MqttMonitorType {
+    fn name(&self) -> &'static str {
+        "mqtt"
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: MqttConfig = parse_config(config)?;
+        if config.broker().0.trim().is_empty() {
+            return Err(AppError::BadRequest("Hostname is required".to_string()));
+        }
+        if config.topic.is_empty() {
+            return Err(AppError::BadRequest("Topic is required".to_string()));
+        }
+        if config.check_type == MqttCheckType::JsonQuery {
+            let path = config.json_path.as_deref().unwrap_or_default();
+            json_query::validate_query(path, &config.json_path_operator)?;
+            if config.expected_value.is_none() {
+                return Err(AppError::BadRequest("Expected value is required".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: MqttConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid MQTT config"),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let start_time = Instant::now();
+        let message = match tokio::time::timeout(timeout, receive(&config)).await {
+            Ok(Ok(message)) => message,
+            Ok(Err(e)) => return CheckResult::down(e),
+            Err(_) => return CheckResult::down("Timeout, Message not received"),
+        };
+        let latency = start_time.elapsed().as_millis() as i32;
+
+        evaluate(&config, &message).with_latency(latency)
+    }
+}
+
+/// Checks the received message against the keyword or JSON query.
+fn evaluate(config: &MqttConfig, message: &str) -> CheckResult {
+    match config.check_type {
+        MqttCheckType::Keyword => {
+            let keyword = config.success_message.as_deref().unwrap_or_default();
+            if message.contains(keyword) {
+                CheckResult::up().with_message(format!("Topic: {}; Message: {}", config.topic, message))
+            } else {
+                CheckResult::down(format!("Message Mismatch - Topic: {}; Message: {}", config.topic, message))
+            }
+        }
+        MqttCheckType::JsonQuery => {
+            let data = match serde_json::from_str(message) {
+                Ok(data) => data,
+                Err(e) => return CheckResult::down(format!("Message is not valid JSON: {}", e)),
+            };
+            let path = config.json_path.as_deref().unwrap_or_default();
+            let expected = config.expected_value.as_deref().unwrap_or_default();
+            match json_query::evaluate_json_query(&data, path, &config.json_path_operator, expected) {
+                Ok((true, _)) => CheckResult::up().with_message("Message received, expected value is found"),
+                Ok((false, value)) => CheckResult::down(format!(
+                    "Message received but value is not equal to expected value, value was: [{}]",
+                    value
+                )),
+                Err(e) => CheckResult::down(e),
+            }
+        }
+    }
+}
+
+/// Connects, subscribes and returns the first message published on the topic.
+async fn receive(config: &MqttConfig) -> Result<String, String> {
+    let (host, tls) = config.broker();
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+
+    let mut options = MqttOptions::new(format!("uptime-kuma_{:08x}", nonce), host, config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    if let Some(username) = &config.username {
+        options.set_credentials(username, config.password.as_deref().unwrap_or_default());
+    }
+    if tls {
+        options.set_transport(Transport::tls_with_default_config());
+    }
+
+    let (client, mut event_loop) = AsyncClient::new(options, 10);
+    client
+        .subscribe(&config.topic, QoS::AtMostOnce)
+        .await
+        .map_err(|_| "Cannot subscribe topic".to_string())?;
+
+    let message = loop {
+        match event_loop.poll().await {
+            Ok(Event::Incoming(Packet::Publish(publish)))
+                if rumqttc::matches(&publish.topic, &config.topic) =>
+            {
+                break String::from_utf8_lossy(&publish.payload).into_owned();
+            }
+            Ok(_) => continue,
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    // Best effort, the connection is dropped either way
+    let _ = client.try_disconnect();
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::monitor::{STATUS_DOWN, STATUS_UP};
+    use serde_json::json;
+
+    fn config(value: Value) -> MqttConfig {
+        parse_config(&value).unwrap()
+    }
+
+    #[test]
+    fn defaults() {
+        let config = config(json!({ "hostname": "broker", "topic": "a/b" }));
+        assert_eq!(config.port, 1883);
+        assert_eq!(config.check_type, MqttCheckType::Keyword);
+        assert_eq!(config.json_path_operator, "==");
+        assert!(!config.tls);
+    }
+
+    #[test]
+    fn broker_strips_scheme() {
+        let plain = config(json!({ "hostname": "broker.local", "topic": "t" }));
+        assert_eq!(plain.broker(), ("broker.local", false));
+
+        let mqtt = config(json!({ "hostname": "mqtt://broker.local", "topic": "t" }));
+        assert_eq!(mqtt.broker(), ("broker.local", false));
+
+        let mqtts = config(json!({ "hostname": "mqtts://broker.local", "topic": "t" }));
+        assert_eq!(mqtts.broker(), ("broker.local", true));
+
+        let tls = config(json!({ "hostname": "mqtt://broker.local", "topic": "t", "tls": true }));
+        assert_eq!(tls.broker(), ("broker.local", true));
+    }
+
+    #[test]
+    fn validate_requires_hostname_and_topic() {
+        let monitor_type = MqttMonitorType;
+        assert!(monitor_type.validate("", &json!({ "hostname": "mqtt://", "topic": "t" })).is_err());
+        assert!(monitor_type.validate("", &json!({ "hostname": "broker", "topic": "" })).is_err());
+        assert!(monitor_type.validate("", &json!({ "hostname": "broker", "topic": "t" })).is_ok());
+        assert!(monitor_type
+            .validate("", &json!({ "hostname": "broker", "topic": "t", "check_type": "json-query", "json_path": "$.a" }))
+            .is_err());
+    }
+
+    #[test]
+    fn keyword_check() {
+        let config = config(json!({ "hostname": "broker", "topic": "t", "success_message": "ok" }));
+
+        let result = evaluate(&config, "status ok");
+        assert_eq!(result.status, STATUS_UP);
+        assert_eq!(result.message.as_deref(), Some("Topic: t; Message: status ok"));
+
+        let result = evaluate(&config, "status failed");
+        assert_eq!(result.status, STATUS_DOWN);
+        assert_eq!(result.message.as_deref(), Some("Message Mismatch - Topic: t; Message: status failed"));
+    }
+
+    #[test]
+    fn json_query_check() {
+        let config = config(json!({
+            "hostname": "broker",
+            "topic": "t",
+            "check_type": "json-query",
+            "json_path": "$.sensors[1].temp",
+            "json_path_operator": "<",
+            "expected_value": "30",
+        }));
+
+        let result = evaluate(&config, r#"{"sensors":[{"temp":50},{"temp":21.5}]}"#);
+        assert_eq!(result.status, STATUS_UP);
+
+        let result = evaluate(&config, r#"{"sensors":[{"temp":50},{"temp":42}]}"#);
+        assert_eq!(result.status, STATUS_DOWN);
+        assert_eq!(
+            result.message.as_deref(),
+            Some("Message received but value is not equal to expected value, value was: [42]")
+        );
+
+        let result = evaluate(&config, "not json");
+        assert_eq!(result.status, STATUS_DOWN);
+        assert!(result.message.unwrap().starts_with("Message is not valid JSON"));
+
+        let result = evaluate(&config, r#"{"sensors":[]}"#);
+        assert_eq!(result.status, STATUS_DOWN);
+    }
+
+    /// Needs a broker on localhost:1883, e.g. `docker run -p 1883:1883 eclipse-mosquitto`
+    /// with anonymous access enabled.
+    #[tokio::test]
+    #[ignore]
+    async fn receives_from_broker() {
+        let topic = "uptime-kuma/test/mqtt";
+        let mut options = MqttOptions::new("uptime-kuma_test_publisher", "localhost", 1883);
+        options.set_keep_alive(Duration::from_secs(5));
+        let (client, mut event_loop) = AsyncClient::new(options, 10);
+        client.publish(topic, QoS::AtLeastOnce, true, "broker ok").await.unwrap();
+        loop {
+            match event_loop.poll().await.unwrap() {
+                Event::Incoming(Packet::PubAck(_)) => break,
+                _ => continue,
+            }
+        }
+
+        let config = config(json!({ "hostname": "mqtt://localhost", "topic": topic }));
+        let message = tokio::time::timeout(Duration::from_secs(5), receive(&config))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(message, "broker ok");
+    }
+}
@@ -0,0 +1,313 @@
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use crate::{
+    models::monitor::Monitor,
+    monitor_conditions::{operators::NUMBER_OPERATORS, variables::ConditionVariable},
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config};
+
+const CONDITION_VARIABLES: &[ConditionVariable] = &[
+    ConditionVariable::new("packet_loss", NUMBER_OPERATORS),
+    ConditionVariable::new("rtt_avg", NUMBER_OPERATORS),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct PingConfig {
+    /// Host to ping. Falls back to the host of the monitor URL.
+    hostname: Option<String>,
+    packet_count: u32,
+    /// Seconds to wait for each reply.
+    per_ping_timeout: u32,
+    /// Highest packet loss percentage still considered up.
+    max_packet_loss: f64,
+}
+
+impl Default for PingConfig {
+    fn default() -> Self {
+        Self {
+            hostname: None,
+            packet_count: 1,
+            per_ping_timeout: 2,
+            max_packet_loss: 0.0,
+        }
+    }
+}
+
+impl PingConfig {
+    fn hostname(&self, url: &str) -> Option<String> {
+        if let Some(hostname) = self.hostname.as_deref().filter(|h| !h.is_empty()) {
+            return Some(hostname.to_string());
+        }
+
+        // Accept both full URLs and bare hostnames
+        let url = if url.contains("://") {
+            Url::parse(url).ok()?
+        } else {
+            Url::parse(&format!("ping://{}", url)).ok()?
+        };
+        url.host_str()
+            .map(|host| host.trim_start_matches('[').trim_end_matches(']').to_string())
+    }
+}
+
+/// Statistics parsed from the output of `ping`.
+#[derive(Debug, Default, Serialize)]
+struct PingStats {
+    transmitted: u32,
+    received: u32,
+    packet_loss: f64,
+    rtt_min: Option<f64>,
+    rtt_avg: Option<f64>,
+    rtt_max: Option<f64>,
+    rtt_mdev: Option<f64>,
+}
+
+/// ICMP ping using the system's `ping` command.
+pub struct PingMonitorType;
+
+#[async_trait]
+impl MonitorType for PingMonitorType {
+    fn name(&self) -> &'static str {
+        "ping"
+    }
+
+    fn validate(&self, url: &str, config: &Value) -> Result<(), AppError> {
+        let config: PingConfig = parse_config(config)?;
+
+        let hostname = config
+            .hostname(url)
+            .ok_or_else(|| AppError::BadRequest("Hostname is required".to_string()))?;
+        if hostname.starts_with('-') || hostname.chars().any(char::is_whitespace) {
+            return Err(AppError::BadRequest(format!("Invalid hostname: {}", hostname)));
+        }
+        if !(1..=100).contains(&config.packet_count) {
+            return Err(AppError::BadRequest("Packet count must be between 1 and 100".to_string()));
+        }
+        if !(1..=60).contains(&config.per_ping_timeout) {
+            return Err(AppError::BadRequest("Per-ping timeout must be between 1 and 60 seconds".to_string()));
+        }
+        if !(0.0..=100.0).contains(&config.max_packet_loss) {
+            return Err(AppError::BadRequest("Max packet loss must be between 0 and 100".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn condition_variables(&self) -> &'static [ConditionVariable] {
+        CONDITION_VARIABLES
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: PingConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid ping config"),
+        };
+        let Some(hostname) = config.hostname(&monitor.url) else {
+            return CheckResult::down("Hostname is required");
+        };
+
+        let command = tokio::process::Command::new("ping")
+            .arg("-c")
+            .arg(config.packet_count.to_string())
+            .arg("-W")
+            .arg(config.per_ping_timeout.to_string())
+            .arg(&hostname)
+            .kill_on_drop(true)
+            .output();
+
+        // Each packet is sent a second apart, plus the wait for the last reply
+        let deadline = (monitor.timeout.max(0) as u64)
+            .max((config.packet_count + config.per_ping_timeout) as u64);
+        let output = match tokio::time::timeout(Duration::from_secs(deadline), command).await {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => return CheckResult::down(e.to_string()),
+            Err(_) => return CheckResult::down(format!("Ping timed out after {}s", deadline)),
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let Some(stats) = parse_output(&stdout) else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr
+                .lines()
+                .chain(stdout.lines())
+                .find(|line| !line.trim().is_empty())
+                .unwrap_or("Ping failed");
+            return CheckResult::down(message.trim());
+        };
+
+        let summary = match (stats.rtt_min, stats.rtt_avg, stats.rtt_max) {
+            (Some(min), Some(avg), Some(max)) => format!(
+                "{} packets, {}% packet loss, rtt min/avg/max/mdev = {}/{}/{}/{} ms",
+                stats.transmitted,
+                stats.packet_loss,
+                min,
+                avg,
+                max,
+                stats.rtt_mdev.map(|mdev| mdev.to_string()).unwrap_or_else(|| "-".to_string())
+            ),
+            _ => format!("{} packets, {}% packet loss", stats.transmitted, stats.packet_loss),
+        };
+        let details = serde_json::to_value(&stats).unwrap_or(Value::Null);
+
+        let result = if stats.received == 0 || stats.packet_loss > config.max_packet_loss {
+            CheckResult::down(summary)
+        } else {
+            CheckResult::up().with_message(summary)
+        };
+        let result = result
+            .with_details(details)
+            .with_variable("packet_loss", stats.packet_loss);
+
+        match stats.rtt_avg {
+            Some(avg) => result.with_latency(avg.round() as i32).with_variable("rtt_avg", avg),
+            None => result,
+        }
+    }
+}
+
+/// Parses the summary lines printed by iputils, BusyBox and BSD `ping`, e.g.
+///
+/// ```text
+/// 3 packets transmitted, 3 received, 0% packet loss, time 2003ms
+/// rtt min/avg/max/mdev = 0.035/0.045/0.056/0.008 ms
+/// ```
+fn parse_output(output: &str) -> Option<PingStats> {
+    let mut stats = PingStats::default();
+    let mut found = false;
+
+    for line in output.lines() {
+        if line.contains("packets transmitted") {
+            for part in line.split(',').map(str::trim) {
+                let number = part.split_whitespace().next().unwrap_or_default();
+                if part.ends_with("packets transmitted") {
+                    stats.transmitted = number.parse().ok()?;
+                } else if part.contains("received") {
+                    stats.received = number.parse().ok()?;
+                } else if part.ends_with("packet loss") {
+                    stats.packet_loss = number.trim_end_matches('%').parse().ok()?;
+                }
+            }
+            found = true;
+        } else if line.contains("min/avg/max") {
+            let values = line.split('=').nth(1)?.split_whitespace().next()?;
+            let mut values = values.split('/').map(|value| value.parse::<f64>().ok());
+            stats.rtt_min = values.next().flatten();
+            stats.rtt_avg = values.next().flatten();
+            stats.rtt_max = values.next().flatten();
+            stats.rtt_mdev = values.next().flatten();
+        }
+    }
+
+    found.then_some(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IPUTILS: &str = "\
+PING example.com (93.184.215.14) 56(84) bytes of data.
+64 bytes from 93.184.215.14 (93.184.215.14): icmp_seq=1 ttl=56 time=11.6 ms
+64 bytes from 93.184.215.14 (93.184.215.14): icmp_seq=2 ttl=56 time=11.5 ms
+64 bytes from 93.184.215.14 (93.184.215.14): icmp_seq=3 ttl=56 time=11.7 ms
+
+--- example.com ping statistics ---
+3 packets transmitted, 3 received, 0% packet loss, time 2003ms
+rtt min/avg/max/mdev = 11.532/11.610/11.698/0.068 ms
+";
+
+    const IPUTILS_UNREACHABLE: &str = "\
+PING 10.0.0.99 (10.0.0.99) 56(84) bytes of data.
+From 10.0.0.1 icmp_seq=1 Destination Host Unreachable
+From 10.0.0.1 icmp_seq=2 Destination Host Unreachable
+From 10.0.0.1 icmp_seq=3 Destination Host Unreachable
+
+--- 10.0.0.99 ping statistics ---
+3 packets transmitted, 0 received, +3 errors, 100% packet loss, time 2046ms
+pipe 3
+";
+
+    const BUSYBOX: &str = "\
+PING 172.17.0.1 (172.17.0.1): 56 data bytes
+64 bytes from 172.17.0.1: seq=0 ttl=64 time=0.101 ms
+64 bytes from 172.17.0.1: seq=1 ttl=64 time=0.093 ms
+
+--- 172.17.0.1 ping statistics ---
+2 packets transmitted, 2 packets received, 0% packet loss
+round-trip min/avg/max = 0.093/0.097/0.101 ms
+";
+
+    const BSD: &str = "\
+PING 192.168.1.1 (192.168.1.1): 56 data bytes
+64 bytes from 192.168.1.1: icmp_seq=0 ttl=64 time=2.412 ms
+Request timeout for icmp_seq 1
+64 bytes from 192.168.1.1: icmp_seq=2 ttl=64 time=3.058 ms
+
+--- 192.168.1.1 ping statistics ---
+4 packets transmitted, 2 packets received, 50.0% packet loss
+round-trip min/avg/max/stddev = 2.412/2.735/3.058/0.323 ms
+";
+
+    #[test]
+    fn parses_iputils() {
+        let stats = parse_output(IPUTILS).unwrap();
+        assert_eq!((stats.transmitted, stats.received), (3, 3));
+        assert_eq!(stats.packet_loss, 0.0);
+        assert_eq!(stats.rtt_min, Some(11.532));
+        assert_eq!(stats.rtt_avg, Some(11.61));
+        assert_eq!(stats.rtt_max, Some(11.698));
+        assert_eq!(stats.rtt_mdev, Some(0.068));
+    }
+
+    #[test]
+    fn parses_iputils_errors() {
+        let stats = parse_output(IPUTILS_UNREACHABLE).unwrap();
+        assert_eq!((stats.transmitted, stats.received), (3, 0));
+        assert_eq!(stats.packet_loss, 100.0);
+        assert_eq!(stats.rtt_avg, None);
+    }
+
+    #[test]
+    fn parses_busybox() {
+        let stats = parse_output(BUSYBOX).unwrap();
+        assert_eq!((stats.transmitted, stats.received), (2, 2));
+        assert_eq!(stats.packet_loss, 0.0);
+        assert_eq!(stats.rtt_avg, Some(0.097));
+        assert_eq!(stats.rtt_max, Some(0.101));
+        assert_eq!(stats.rtt_mdev, None);
+    }
+
+    #[test]
+    fn parses_bsd() {
+        let stats = parse_output(BSD).unwrap();
+        assert_eq!((stats.transmitted, stats.received), (4, 2));
+        assert_eq!(stats.packet_loss, 50.0);
+        assert_eq!(stats.rtt_min, Some(2.412));
+        assert_eq!(stats.rtt_mdev, Some(0.323));
+    }
+
+    #[test]
+    fn no_summary() {
+        assert!(parse_output("ping: unknown.invalid: Name or service not known\n").is_none());
+        assert!(parse_output("PING 10.0.0.99 (10.0.0.99) 56(84) bytes of data.\n").is_none());
+        assert!(parse_output("").is_none());
+    }
+
+    #[test]
+    fn hostname_from_url() {
+        let config = PingConfig::default();
+        assert_eq!(config.hostname("https://example.com:8080/path").as_deref(), Some("example.com"));
+        assert_eq!(config.hostname("example.com").as_deref(), Some("example.com"));
+        assert_eq!(config.hostname("http://[::1]/").as_deref(), Some("::1"));
+        let config = PingConfig {
+            hostname: Some("10.0.0.1".to_string()),
+            ..PingConfig::default()
+        };
+        assert_eq!(config.hostname("https://example.com").as_deref(), Some("10.0.0.1"));
+    }
+}
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config};
+
+#[derive(Debug, Deserialize)]
+struct PortConfig {
+    hostname: String,
+    port: u16,
+}
+
+/// Opens a TCP connection to `hostname:port`.
+pub struct PortMonitorType;
+
+#[async_trait]
+impl MonitorType for PortMonitorType {
+    fn name(&self) -> &'static str {
+        "port"
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: PortConfig = parse_config(config)?;
+        if config.hostname.trim().is_empty() {
+            return Err(AppError::BadRequest("Hostname is required".to_string()));
+        }
+        if config.port == 0 {
+            return Err(AppError::BadRequest("Port must be between 1 and 65535".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: PortConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid port config"),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let start_time = Instant::now();
+        let connect = TcpStream::connect((config.hostname.as_str(), config.port));
+
+        match tokio::time::timeout(timeout, connect).await {
+            Ok(Ok(_)) => CheckResult::up()
+                .with_latency(start_time.elapsed().as_millis() as i32),
+            Ok(Err(e)) => CheckResult::down(format!(
+                "Cannot connect to {}:{}: {}",
+                config.hostname, config.port, e
+            )),
+            Err(_) => CheckResult::down(format!(
+                "Connection to {}:{} timed out after {}s",
+                config.hostname,
+                config.port,
+                timeout.as_secs()
+            )),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::models::monitor::{Monitor, STATUS_PENDING};
+use super::{CheckResult, MonitorType};
+
+/// Passive monitor kept up by requests to `/api/push/:token`.
+///
+/// The scheduled check only marks the monitor down when no push arrived
+/// within the monitor's interval.
+pub struct PushMonitorType;
+
+#[async_trait]
+impl MonitorType for PushMonitorType {
+    fn name(&self) -> &'static str {
+        "push"
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        check_window(monitor, Utc::now())
+    }
+}
+
+/// Down once the time window since the last push has passed, otherwise
+/// skipped until the window ends.
+///
+/// A pending monitor is retried after `retry_interval` instead of its interval.
+fn check_window(monitor: &Monitor, now: DateTime<Utc>) -> CheckResult {
+    let window = if monitor.status == STATUS_PENDING && monitor.retry_interval > 0 {
+        monitor.retry_interval
+    } else {
+        monitor.interval
+    };
+
+    // A new monitor gets one window to receive its first push
+    let last_push = monitor.last_check.unwrap_or(monitor.created_at);
+    let elapsed = now.signed_duration_since(last_push).num_seconds();
+
+    if elapsed >= window as i64 {
+        CheckResult::down("No heartbeat in the time window").with_latency(0)
+    } else {
+        CheckResult::skipped().with_next_check(window - elapsed as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::monitor::{STATUS_DOWN, STATUS_UP};
+    use chrono::Duration;
+
+    fn monitor(status: &str, last_push: DateTime<Utc>) -> Monitor {
+        Monitor {
+            interval: 60,
+            retry_interval: 20,
+            status: status.to_string(),
+            last_check: Some(last_push),
+            ..Monitor::for_test(1, "push")
+        }
+    }
+
+    #[test]
+    fn skipped_until_window_ends() {
+        let now = Utc::now();
+        let result = check_window(&monitor(STATUS_UP, now - Duration::seconds(45)), now);
+        assert!(result.skipped);
+        assert_eq!(result.next_check, Some(15));
+    }
+
+    #[test]
+    fn down_when_window_passed() {
+        let now = Utc::now();
+        let result = check_window(&monitor(STATUS_UP, now - Duration::seconds(60)), now);
+        assert!(!result.skipped);
+        assert_eq!(result.status, STATUS_DOWN);
+        assert_eq!(result.message.as_deref(), Some("No heartbeat in the time window"));
+    }
+
+    #[test]
+    fn pending_uses_retry_interval() {
+        let now = Utc::now();
+        let result = check_window(&monitor(STATUS_PENDING, now - Duration::seconds(5)), now);
+        assert_eq!(result.next_check, Some(15));
+
+        let result = check_window(&monitor(STATUS_PENDING, now - Duration::seconds(25)), now);
+        assert_eq!(result.status, STATUS_DOWN);
+
+        let no_retry_interval = Monitor {
+            retry_interval: 0,
+            ..monitor(STATUS_PENDING, now - Duration::seconds(25))
+        };
+        assert_eq!(check_window(&no_retry_interval, now).next_check, Some(35));
+    }
+
+    #[test]
+    fn new_monitor_counts_from_creation() {
+        let now = Utc::now();
+        let new = Monitor {
+            last_check: None,
+            created_at: now - Duration::seconds(10),
+            ..monitor(STATUS_UP, now)
+        };
+        assert_eq!(check_window(&new, now).next_check, Some(50));
+    }
+}
@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode, Url};
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, parse_config};
+
+#[derive(Debug, Deserialize)]
+struct RabbitMqConfig {
+    /// Management API base URLs, tried in order.
+    nodes: Vec<String>,
+    #[serde(default)]
+    username: String,
+    #[serde(default)]
+    password: String,
+}
+
+/// Checks node health through the RabbitMQ management API. Up as soon as one
+/// of the nodes reports no alarms.
+#[derive(Default)]
+pub struct RabbitMqMonitorType {
+    client: Client,
+}
+
+impl RabbitMqMonitorType {
+    /// Queries the alarms health check of one node.
+    async fn check_node(&self, node: &str, config: &RabbitMqConfig, timeout: Duration) -> Result<(), String> {
+        let url = alarms_url(node).map_err(|e| format!("Invalid node URL {}: {}", node, e))?;
+        let response = self
+            .client
+            .get(url)
+            .basic_auth(&config.username, Some(&config.password))
+            .header("Accept", "application/json")
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| if e.is_timeout() { "Request timed out".to_string() } else { e.to_string() })?;
+
+        match response.status() {
+            StatusCode::OK => Ok(()),
+            StatusCode::SERVICE_UNAVAILABLE => {
+                let body: Value = response.json().await.unwrap_or(Value::Null);
+                Err(body["reason"].as_str().unwrap_or("Service unavailable").to_string())
+            }
+            status => Err(format!(
+                "{} - {}",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or_default()
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl MonitorType for RabbitMqMonitorType {
+    fn name(&self) -> &'static str {
+        "rabbitmq"
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: RabbitMqConfig = parse_config(config)?;
+        if config.nodes.is_empty() {
+            return Err(AppError::BadRequest("At least one node is required".to_string()));
+        }
+        for node in &config.nodes {
+            alarms_url(node).map_err(|_| AppError::BadRequest(format!("Invalid node URL: {}", node)))?;
+        }
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: RabbitMqConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid RabbitMQ config"),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let mut message = "No nodes configured".to_string();
+        for node in &config.nodes {
+            let start_time = Instant::now();
+            match self.check_node(node, &config, timeout).await {
+                Ok(()) => {
+                    let latency = start_time.elapsed().as_millis() as i32;
+                    return CheckResult::up().with_message("OK").with_latency(latency);
+                }
+                Err(e) => message = e,
+            }
+        }
+
+        CheckResult::down(message)
+    }
+}
+
+/// Health check endpoint under a node's base URL, keeping any path prefix.
+fn alarms_url(node: &str) -> Result<Url, String> {
+    let base = if node.ends_with('/') {
+        Url::parse(node)
+    } else {
+        Url::parse(&format!("{}/", node))
+    };
+    base.and_then(|base| base.join("api/health/checks/alarms"))
+        .map_err(|e| e.to_string())
+}
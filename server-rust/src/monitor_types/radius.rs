@@ -0,0 +1,421 @@
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use md5::{Digest, Md5};
+use rand::Rng;
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use crate::{
+    models::monitor::Monitor,
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, connect_udp, parse_config};
+
+const ACCESS_REQUEST: u8 = 1;
+const ACCESS_ACCEPT: u8 = 2;
+const ACCESS_REJECT: u8 = 3;
+const ACCESS_CHALLENGE: u8 = 11;
+
+const ATTR_USER_NAME: u8 = 1;
+const ATTR_USER_PASSWORD: u8 = 2;
+const ATTR_CALLED_STATION_ID: u8 = 30;
+const ATTR_CALLING_STATION_ID: u8 = 31;
+const ATTR_NAS_IDENTIFIER: u8 = 32;
+const ATTR_MESSAGE_AUTHENTICATOR: u8 = 80;
+
+/// Largest packet allowed by RFC 2865.
+const MAX_PACKET_LENGTH: usize = 4096;
+
+#[derive(Debug, Deserialize)]
+struct RadiusConfig {
+    hostname: String,
+    #[serde(default = "default_port")]
+    port: u16,
+    username: String,
+    password: String,
+    secret: String,
+    nas_identifier: Option<String>,
+    called_station_id: Option<String>,
+    calling_station_id: Option<String>,
+    /// Also up on Access-Reject, which still proves the server is answering.
+    #[serde(default)]
+    accept_reject: bool,
+}
+
+fn default_port() -> u16 {
+    1812
+}
+
+/// Sends a RADIUS Access-Request (RFC 2865) and expects an Access-Accept.
+pub struct RadiusMonitorType;
+
+#[async_trait]
+impl MonitorType for RadiusMonitorType {
+    fn name(&self) -> &'static str {
+        "radius"
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: RadiusConfig = parse_config(config)?;
+        if config.hostname.trim().is_empty() {
+            return Err(AppError::BadRequest("Hostname is required".to_string()));
+        }
+        if config.secret.is_empty() {
+            return Err(AppError::BadRequest("Shared secret is required".to_string()));
+        }
+        if config.password.len() > 128 {
+            return Err(AppError::BadRequest("Password must be at most 128 bytes".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: RadiusConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid RADIUS config"),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let start_time = Instant::now();
+        let code = match tokio::time::timeout(timeout, authenticate(&config)).await {
+            Ok(Ok(code)) => code,
+            Ok(Err(e)) => return CheckResult::down(e),
+            Err(_) => return CheckResult::down(format!("No response after {}s", timeout.as_secs())),
+        };
+        let latency = start_time.elapsed().as_millis() as i32;
+
+        let result = match code {
+            ACCESS_ACCEPT => CheckResult::up().with_message("Access-Accept"),
+            ACCESS_REJECT if config.accept_reject => CheckResult::up().with_message("Access-Reject"),
+            ACCESS_REJECT => CheckResult::down("Access-Reject"),
+            ACCESS_CHALLENGE => CheckResult::down("Access-Challenge"),
+            other => CheckResult::down(format!("Unexpected response code {}", other)),
+        };
+        result.with_latency(latency)
+    }
+}
+
+/// Sends the Access-Request and returns the code of the verified response.
+async fn authenticate(config: &RadiusConfig) -> Result<u8, String> {
+    let identifier: u8 = rand::thread_rng().gen();
+    let authenticator: [u8; 16] = rand::thread_rng().gen();
+    let request = access_request(config, identifier, &authenticator)?;
+
+    let socket = connect_udp(&config.hostname, config.port).await?;
+    socket.send(&request).await.map_err(|e| e.to_string())?;
+
+    let mut buffer = [0u8; MAX_PACKET_LENGTH];
+    loop {
+        let length = socket.recv(&mut buffer).await.map_err(|e| e.to_string())?;
+        let response = &buffer[..length];
+
+        // Ignore stray packets, e.g. late answers to an earlier check
+        if response.len() < 20 || response[1] != identifier {
+            continue;
+        }
+        if usize::from(u16::from_be_bytes([response[2], response[3]])) != response.len() {
+            return Err("Malformed response".to_string());
+        }
+        if !verify_response(response, &authenticator, config.secret.as_bytes()) {
+            return Err("Invalid response authenticator, check the shared secret".to_string());
+        }
+        if !verify_message_authenticator(response, &authenticator, config.secret.as_bytes()) {
+            return Err("Invalid Message-Authenticator, check the shared secret".to_string());
+        }
+        return Ok(response[0]);
+    }
+}
+
+fn access_request(config: &RadiusConfig, identifier: u8, authenticator: &[u8; 16]) -> Result<Vec<u8>, String> {
+    let secret = config.secret.as_bytes();
+
+    let mut attributes = Vec::new();
+    push_attribute(&mut attributes, ATTR_USER_NAME, config.username.as_bytes())?;
+    push_attribute(&mut attributes, ATTR_USER_PASSWORD, &hide_password(config.password.as_bytes(), secret, authenticator))?;
+    for (attribute, value) in [
+        (ATTR_NAS_IDENTIFIER, &config.nas_identifier),
+        (ATTR_CALLED_STATION_ID, &config.called_station_id),
+        (ATTR_CALLING_STATION_ID, &config.calling_station_id),
+    ] {
+        if let Some(value) = value.as_deref().filter(|value| !value.is_empty()) {
+            push_attribute(&mut attributes, attribute, value.as_bytes())?;
+        }
+    }
+    // Signed below, once the rest of the packet is known
+    let signature_offset = 20 + attributes.len() + 2;
+    push_attribute(&mut attributes, ATTR_MESSAGE_AUTHENTICATOR, &[0; 16])?;
+
+    let length = 20 + attributes.len();
+    let mut packet = Vec::with_capacity(length);
+    packet.push(ACCESS_REQUEST);
+    packet.push(identifier);
+    packet.extend_from_slice(&(length as u16).to_be_bytes());
+    packet.extend_from_slice(authenticator);
+    packet.extend_from_slice(&attributes);
+
+    let mut mac = Hmac::<Md5>::new_from_slice(secret).map_err(|e| e.to_string())?;
+    mac.update(&packet);
+    packet[signature_offset..signature_offset + 16].copy_from_slice(&mac.finalize().into_bytes());
+
+    Ok(packet)
+}
+
+fn push_attribute(attributes: &mut Vec<u8>, attribute: u8, value: &[u8]) -> Result<(), String> {
+    if value.len() > 253 {
+        return Err(format!("RADIUS attribute {} is too long", attribute));
+    }
+    attributes.push(attribute);
+    attributes.push(value.len() as u8 + 2);
+    attributes.extend_from_slice(value);
+    Ok(())
+}
+
+/// Encrypts User-Password as described in RFC 2865, section 5.2.
+fn hide_password(password: &[u8], secret: &[u8], authenticator: &[u8; 16]) -> Vec<u8> {
+    let mut padded = password.to_vec();
+    padded.resize(password.len().div_ceil(16).max(1) * 16, 0);
+
+    let mut hidden: Vec<u8> = Vec::with_capacity(padded.len());
+    for chunk in padded.chunks(16) {
+        let previous = match hidden.len() {
+            0 => &authenticator[..],
+            n => &hidden[n - 16..],
+        };
+        let key = Md5::new().chain_update(secret).chain_update(previous).finalize();
+        let block: Vec<u8> = chunk.iter().zip(key.iter()).map(|(p, k)| p ^ k).collect();
+        hidden.extend_from_slice(&block);
+    }
+    hidden
+}
+
+/// Checks the Response Authenticator: MD5(Code + Identifier + Length +
+/// Request Authenticator + Attributes + Secret).
+fn verify_response(response: &[u8], request_authenticator: &[u8; 16], secret: &[u8]) -> bool {
+    let expected = Md5::new()
+        .chain_update(&response[..4])
+        .chain_update(request_authenticator)
+        .chain_update(&response[20..])
+        .chain_update(secret)
+        .finalize();
+    expected.as_slice() == &response[4..20]
+}
+
+/// Checks the Message-Authenticator of a response that carries one
+/// (RFC 3579, section 3.2): HMAC-MD5 of the packet with the Request
+/// Authenticator in place of the Response Authenticator and the attribute
+/// value zeroed.
+fn verify_message_authenticator(response: &[u8], request_authenticator: &[u8; 16], secret: &[u8]) -> bool {
+    let mut offset = 20;
+    while offset + 2 <= response.len() {
+        let (attribute, length) = (response[offset], usize::from(response[offset + 1]));
+        if length < 2 || offset + length > response.len() {
+            return false;
+        }
+        if attribute == ATTR_MESSAGE_AUTHENTICATOR {
+            if length != 18 {
+                return false;
+            }
+            let mut packet = response.to_vec();
+            packet[4..20].copy_from_slice(request_authenticator);
+            packet[offset + 2..offset + 18].fill(0);
+            let Ok(mut mac) = Hmac::<Md5>::new_from_slice(secret) else {
+                return false;
+            };
+            mac.update(&packet);
+            return mac.verify_slice(&response[offset + 2..offset + 18]).is_ok();
+        }
+        offset += length;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::net::UdpSocket;
+
+    const SECRET: &[u8] = b"xyzzy5461";
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn authenticator(s: &str) -> [u8; 16] {
+        hex(s).try_into().unwrap()
+    }
+
+    fn config(port: u16, secret: &str) -> RadiusConfig {
+        parse_config(&json!({
+            "hostname": "localhost",
+            "port": port,
+            "username": "nemo",
+            "password": "arctangent",
+            "secret": secret,
+            "nas_identifier": "uptime-kuma",
+        }))
+        .unwrap()
+    }
+
+    /// Reverses `hide_password`, as the server would.
+    fn reveal_password(hidden: &[u8], secret: &[u8], authenticator: &[u8; 16]) -> Vec<u8> {
+        let mut password = Vec::new();
+        let mut previous = &authenticator[..];
+        for chunk in hidden.chunks(16) {
+            let key = Md5::new().chain_update(secret).chain_update(previous).finalize();
+            password.extend(chunk.iter().zip(key.iter()).map(|(c, k)| c ^ k));
+            previous = chunk;
+        }
+        while password.last() == Some(&0) {
+            password.pop();
+        }
+        password
+    }
+
+    /// Returns the value of the first attribute of the given type.
+    fn attribute(packet: &[u8], attribute: u8) -> Option<(usize, &[u8])> {
+        let mut offset = 20;
+        while offset < packet.len() {
+            let length = usize::from(packet[offset + 1]);
+            if packet[offset] == attribute {
+                return Some((offset, &packet[offset + 2..offset + length]));
+            }
+            offset += length;
+        }
+        None
+    }
+
+    /// Access-Accept for `request`, signed with a Message-Authenticator.
+    fn accept(request: &[u8], secret: &[u8]) -> Vec<u8> {
+        let mut packet = vec![ACCESS_ACCEPT, request[1], 0, 38];
+        packet.extend_from_slice(&request[4..20]);
+        packet.extend_from_slice(&[ATTR_MESSAGE_AUTHENTICATOR, 18]);
+        packet.extend_from_slice(&[0; 16]);
+        let mut mac = Hmac::<Md5>::new_from_slice(secret).unwrap();
+        mac.update(&packet);
+        packet[22..38].copy_from_slice(&mac.finalize().into_bytes());
+
+        let response_authenticator = Md5::new().chain_update(&packet).chain_update(secret).finalize();
+        packet[4..20].copy_from_slice(&response_authenticator);
+        packet
+    }
+
+    #[test]
+    fn hide_password_rfc2865_example() {
+        // RFC 2865, section 7.1
+        let request_authenticator = authenticator("0f403f9473978057bd83d5cb98f4227a");
+        assert_eq!(
+            hide_password(b"arctangent", SECRET, &request_authenticator),
+            hex("0dbe708d93d413ce3196e43f782a0aee")
+        );
+    }
+
+    #[test]
+    fn hide_password_chains_blocks() {
+        let request_authenticator = authenticator("000102030405060708090a0b0c0d0e0f");
+        for password in [&b""[..], b"sixteen byte pwd", b"a password longer than one block"] {
+            let hidden = hide_password(password, SECRET, &request_authenticator);
+            assert_eq!(hidden.len(), password.len().div_ceil(16).max(1) * 16);
+            assert_eq!(reveal_password(&hidden, SECRET, &request_authenticator), password);
+        }
+    }
+
+    #[test]
+    fn verify_response_rfc2865_example() {
+        // Access-Accept answering the request of RFC 2865, section 7.1
+        let request_authenticator = authenticator("0f403f9473978057bd83d5cb98f4227a");
+        let response = hex("0200002686fe220e7624ba2a1005f6bf9b55e0b20606000000010f06000000000e06c0a80103");
+        assert!(verify_response(&response, &request_authenticator, SECRET));
+        assert!(!verify_response(&response, &request_authenticator, b"wrong"));
+        assert!(verify_message_authenticator(&response, &request_authenticator, SECRET));
+    }
+
+    #[test]
+    fn access_request_is_signed() {
+        let request_authenticator = authenticator("0f403f9473978057bd83d5cb98f4227a");
+        let request = access_request(&config(1812, "xyzzy5461"), 7, &request_authenticator).unwrap();
+        assert_eq!(request[0], ACCESS_REQUEST);
+        assert_eq!(request[1], 7);
+        assert_eq!(usize::from(u16::from_be_bytes([request[2], request[3]])), request.len());
+        assert_eq!(attribute(&request, ATTR_USER_NAME).unwrap().1, b"nemo");
+        assert_eq!(attribute(&request, ATTR_NAS_IDENTIFIER).unwrap().1, b"uptime-kuma");
+        assert_eq!(
+            attribute(&request, ATTR_USER_PASSWORD).unwrap().1,
+            hex("0dbe708d93d413ce3196e43f782a0aee")
+        );
+
+        let (offset, signature) = attribute(&request, ATTR_MESSAGE_AUTHENTICATOR).unwrap();
+        let mut unsigned = request.clone();
+        unsigned[offset + 2..offset + 18].fill(0);
+        let mut mac = Hmac::<Md5>::new_from_slice(SECRET).unwrap();
+        mac.update(&unsigned);
+        assert_eq!(signature, mac.finalize().into_bytes().as_slice());
+    }
+
+    #[test]
+    fn message_authenticator_check() {
+        let request = access_request(&config(1812, "xyzzy5461"), 1, &[9; 16]).unwrap();
+        let response = accept(&request, SECRET);
+        assert!(verify_response(&response, &[9; 16], SECRET));
+        assert!(verify_message_authenticator(&response, &[9; 16], SECRET));
+        assert!(!verify_message_authenticator(&response, &[9; 16], b"wrong"));
+
+        let mut tampered = response.clone();
+        tampered[30] ^= 1;
+        assert!(!verify_message_authenticator(&tampered, &[9; 16], SECRET));
+
+        let mut truncated = response;
+        truncated[21] = 40;
+        assert!(!verify_message_authenticator(&truncated, &[9; 16], SECRET));
+    }
+
+    /// Answers one Access-Request on `socket` with a signed Access-Accept.
+    async fn respond(socket: UdpSocket, secret: &'static [u8]) -> Vec<u8> {
+        let mut buffer = [0u8; MAX_PACKET_LENGTH];
+        let (length, peer) = socket.recv_from(&mut buffer).await.unwrap();
+        let request = buffer[..length].to_vec();
+        socket.send_to(&accept(&request, secret), peer).await.unwrap();
+        request
+    }
+
+    #[tokio::test]
+    async fn authenticate_against_local_server() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let server = tokio::spawn(respond(socket, SECRET));
+
+        let mut config = config(port, "xyzzy5461");
+        config.hostname = "127.0.0.1".to_string();
+        assert_eq!(authenticate(&config).await, Ok(ACCESS_ACCEPT));
+
+        let request = server.await.unwrap();
+        let (_, hidden) = attribute(&request, ATTR_USER_PASSWORD).unwrap();
+        let request_authenticator: [u8; 16] = request[4..20].try_into().unwrap();
+        assert_eq!(reveal_password(hidden, SECRET, &request_authenticator), b"arctangent");
+    }
+
+    #[tokio::test]
+    async fn authenticate_rejects_wrong_secret() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        tokio::spawn(respond(socket, b"other secret"));
+
+        let mut config = config(port, "xyzzy5461");
+        config.hostname = "127.0.0.1".to_string();
+        assert!(authenticate(&config).await.unwrap_err().contains("shared secret"));
+    }
+
+    #[tokio::test]
+    async fn authenticate_over_ipv6() {
+        // Skip where the loopback has no IPv6 address
+        let Ok(socket) = UdpSocket::bind("[::1]:0").await else {
+            return;
+        };
+        let port = socket.local_addr().unwrap().port();
+        tokio::spawn(respond(socket, SECRET));
+
+        let mut config = config(port, "xyzzy5461");
+        config.hostname = "::1".to_string();
+        assert_eq!(authenticate(&config).await, Ok(ACCESS_ACCEPT));
+    }
+}
@@ -0,0 +1,334 @@
+//! BER encoding of the ASN.1 types used in SNMP messages.
+
+pub const INTEGER: u8 = 0x02;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+
+/// Encodes a value with the given tag and already encoded content.
+pub fn encode(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let length = content.len();
+    if length < 0x80 {
+        out.push(length as u8);
+    } else {
+        let bytes = (length as u32).to_be_bytes();
+        let skip = bytes.iter().take_while(|&&b| b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+pub fn constructed(tag: u8, parts: &[&[u8]]) -> Vec<u8> {
+    encode(tag, &parts.concat())
+}
+
+pub fn sequence(parts: &[&[u8]]) -> Vec<u8> {
+    constructed(SEQUENCE, parts)
+}
+
+pub fn integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    // Drop leading bytes that only repeat the sign bit
+    let mut start = 0;
+    while start < bytes.len() - 1 {
+        let redundant = (bytes[start] == 0x00 && bytes[start + 1] & 0x80 == 0)
+            || (bytes[start] == 0xFF && bytes[start + 1] & 0x80 != 0);
+        if !redundant {
+            break;
+        }
+        start += 1;
+    }
+    encode(INTEGER, &bytes[start..])
+}
+
+pub fn octet_string(value: &[u8]) -> Vec<u8> {
+    encode(OCTET_STRING, value)
+}
+
+pub fn null() -> Vec<u8> {
+    encode(NULL, &[])
+}
+
+pub fn object_identifier(arcs: &[u32]) -> Vec<u8> {
+    let mut content = Vec::new();
+    let first = arcs[0] * 40 + arcs.get(1).copied().unwrap_or_default();
+    for &arc in std::iter::once(&first).chain(arcs.iter().skip(2)) {
+        let mut chunk = vec![(arc & 0x7F) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            chunk.push((rest & 0x7F) as u8 | 0x80);
+            rest >>= 7;
+        }
+        content.extend(chunk.iter().rev());
+    }
+    encode(OBJECT_IDENTIFIER, &content)
+}
+
+/// Parses a dotted OID such as `1.3.6.1.2.1.1.3.0`, with or without a
+/// leading dot.
+pub fn parse_oid(oid: &str) -> Option<Vec<u32>> {
+    let arcs = oid
+        .trim()
+        .trim_start_matches('.')
+        .split('.')
+        .map(|arc| arc.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    let valid = arcs.len() >= 2
+        && arcs[0] <= 2
+        && (arcs[0] == 2 || arcs[1] < 40)
+        && arcs[1] <= u32::MAX - 80;
+    valid.then_some(arcs)
+}
+
+/// Formats the content of an encoded OID in dotted notation.
+pub fn format_oid(content: &[u8]) -> Result<String, String> {
+    let mut arcs = Vec::new();
+    let mut arc: u32 = 0;
+    for &byte in content {
+        arc = arc
+            .checked_mul(128)
+            .ok_or_else(|| "Invalid object identifier".to_string())?
+            | (byte & 0x7F) as u32;
+        if byte & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (arc / 40).min(2);
+                arcs.push(first);
+                arcs.push(arc - first * 40);
+            } else {
+                arcs.push(arc);
+            }
+            arc = 0;
+        }
+    }
+    if arcs.is_empty() {
+        return Err("Invalid object identifier".to_string());
+    }
+    Ok(arcs.iter().map(u32::to_string).collect::<Vec<_>>().join("."))
+}
+
+pub fn decode_integer(content: &[u8]) -> Result<i64, String> {
+    if content.is_empty() || content.len() > 8 {
+        return Err("Invalid integer".to_string());
+    }
+    let initial = if content[0] & 0x80 != 0 { -1 } else { 0 };
+    Ok(content.iter().fold(initial, |value, &byte| (value << 8) | byte as i64))
+}
+
+/// Decodes the unsigned application types (counters, gauges, time ticks).
+pub fn decode_unsigned(content: &[u8]) -> Result<u64, String> {
+    let skip = content.iter().take_while(|&&b| b == 0).count();
+    if content.is_empty() || content.len() - skip > 8 {
+        return Err("Invalid unsigned integer".to_string());
+    }
+    Ok(content[skip..].iter().fold(0, |value, &byte| (value << 8) | byte as u64))
+}
+
+/// A decoded tag and content, with the content's offset in the message.
+#[derive(Debug, Clone, Copy)]
+pub struct Tlv<'a> {
+    pub tag: u8,
+    pub content: &'a [u8],
+    pub offset: usize,
+}
+
+impl<'a> Tlv<'a> {
+    /// Reader over the values nested in this one.
+    pub fn reader(&self) -> Reader<'a> {
+        Reader {
+            data: self.content,
+            offset: self.offset,
+        }
+    }
+}
+
+/// Reads consecutive values from a message.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn read(&mut self) -> Result<Tlv<'a>, String> {
+        let truncated = || "Truncated response".to_string();
+        let (&tag, rest) = self.data.split_first().ok_or_else(truncated)?;
+        let (&first, rest) = rest.split_first().ok_or_else(truncated)?;
+
+        let (length, header) = if first & 0x80 == 0 {
+            (first as usize, 2)
+        } else {
+            let count = (first & 0x7F) as usize;
+            if count == 0 || count > 4 || rest.len() < count {
+                return Err("Invalid length".to_string());
+            }
+            let length = rest[..count].iter().fold(0, |length, &byte| (length << 8) | byte as usize);
+            (length, 2 + count)
+        };
+        if self.data.len() - header < length {
+            return Err(truncated());
+        }
+
+        let tlv = Tlv {
+            tag,
+            content: &self.data[header..header + length],
+            offset: self.offset + header,
+        };
+        self.data = &self.data[header + length..];
+        self.offset += header + length;
+        Ok(tlv)
+    }
+
+    pub fn expect(&mut self, tag: u8) -> Result<Tlv<'a>, String> {
+        let tlv = self.read()?;
+        if tlv.tag != tag {
+            return Err(format!("Unexpected BER tag 0x{:02x}, expected 0x{:02x}", tlv.tag, tag));
+        }
+        Ok(tlv)
+    }
+
+    pub fn integer(&mut self) -> Result<i64, String> {
+        decode_integer(self.expect(INTEGER)?.content)
+    }
+
+    pub fn octet_string(&mut self) -> Result<&'a [u8], String> {
+        Ok(self.expect(OCTET_STRING)?.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_one(data: &[u8]) -> Tlv<'_> {
+        let mut reader = Reader::new(data);
+        let tlv = reader.read().unwrap();
+        assert!(reader.is_empty());
+        tlv
+    }
+
+    #[test]
+    fn integer_encoding() {
+        let cases: &[(i64, &[u8])] = &[
+            (0, &[0x02, 0x01, 0x00]),
+            (127, &[0x02, 0x01, 0x7F]),
+            (128, &[0x02, 0x02, 0x00, 0x80]),
+            (256, &[0x02, 0x02, 0x01, 0x00]),
+            (-1, &[0x02, 0x01, 0xFF]),
+            (-128, &[0x02, 0x01, 0x80]),
+            (-129, &[0x02, 0x02, 0xFF, 0x7F]),
+            (65507, &[0x02, 0x03, 0x00, 0xFF, 0xE3]),
+        ];
+        for &(value, encoded) in cases {
+            assert_eq!(integer(value), encoded, "{}", value);
+        }
+    }
+
+    #[test]
+    fn integer_round_trip() {
+        for value in [0, 1, -1, 127, -128, 32767, -32769, i32::MAX as i64, i32::MIN as i64, i64::MAX, i64::MIN] {
+            let encoded = integer(value);
+            assert_eq!(Reader::new(&encoded).integer().unwrap(), value);
+        }
+        assert!(decode_integer(&[]).is_err());
+        assert!(decode_integer(&[0; 9]).is_err());
+    }
+
+    #[test]
+    fn unsigned_decoding() {
+        assert_eq!(decode_unsigned(&[0x01, 0xE2, 0x40]).unwrap(), 123456);
+        // Counter32 values with the high bit set carry a leading zero byte
+        assert_eq!(decode_unsigned(&[0x00, 0xFF, 0xFF, 0xFF, 0xFF]).unwrap(), u32::MAX as u64);
+        assert_eq!(decode_unsigned(&[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]).unwrap(), u64::MAX);
+        assert!(decode_unsigned(&[0x01, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        assert!(decode_unsigned(&[]).is_err());
+    }
+
+    #[test]
+    fn object_identifier_encoding() {
+        assert_eq!(
+            object_identifier(&[1, 3, 6, 1, 2, 1, 1, 3, 0]),
+            [0x06, 0x08, 0x2B, 0x06, 0x01, 0x02, 0x01, 0x01, 0x03, 0x00]
+        );
+        // Arcs above 127 take several bytes
+        assert_eq!(
+            object_identifier(&[1, 3, 6, 1, 4, 1, 311, 21, 20]),
+            [0x06, 0x09, 0x2B, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37, 0x15, 0x14]
+        );
+        // The first two arcs share a byte, which itself overflows for 2.999
+        assert_eq!(object_identifier(&[2, 999, 3]), [0x06, 0x03, 0x88, 0x37, 0x03]);
+    }
+
+    #[test]
+    fn object_identifier_round_trip() {
+        for oid in [
+            "1.3.6.1.2.1.1.5.0",
+            "1.3.6.1.4.1.311.21.20",
+            "1.3.6.1.4.1.2021.10.1.3.1",
+            "2.999.3",
+            "0.0",
+            "1.3.6.1.4.1.4294967295",
+        ] {
+            let arcs = parse_oid(oid).unwrap();
+            let encoded = object_identifier(&arcs);
+            assert_eq!(format_oid(read_one(&encoded).content).unwrap(), oid);
+        }
+    }
+
+    #[test]
+    fn parse_oid_formats() {
+        assert_eq!(parse_oid(".1.3.6.1.2.1.1.3.0"), parse_oid("1.3.6.1.2.1.1.3.0"));
+        assert_eq!(parse_oid(" 1.3.6 "), Some(vec![1, 3, 6]));
+        for invalid in ["", "1", "3.1", "1.40", "1.3.x", "1..3", "1.3.4294967296", "-1.3"] {
+            assert_eq!(parse_oid(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn format_oid_rejects_invalid() {
+        assert!(format_oid(&[]).is_err());
+        // More than 32 bits in one arc
+        assert!(format_oid(&[0x2B, 0x90, 0x80, 0x80, 0x80, 0x80, 0x00]).is_err());
+    }
+
+    #[test]
+    fn long_lengths() {
+        let content = vec![0xAB; 300];
+        let encoded = octet_string(&content);
+        assert_eq!(encoded[..4], [0x04, 0x82, 0x01, 0x2C]);
+        let tlv = read_one(&encoded);
+        assert_eq!(tlv.content, &content[..]);
+        assert_eq!(tlv.offset, 4);
+
+        assert_eq!(octet_string(&[0; 200])[..3], [0x04, 0x81, 0xC8]);
+    }
+
+    #[test]
+    fn nested_offsets() {
+        let message = sequence(&[&integer(3), &octet_string(b"abc")]);
+        let mut reader = read_one(&message).reader();
+        assert_eq!(reader.integer().unwrap(), 3);
+        let tlv = reader.expect(OCTET_STRING).unwrap();
+        assert_eq!(&message[tlv.offset..tlv.offset + 3], b"abc");
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed() {
+        assert!(Reader::new(&[0x04]).read().is_err());
+        assert!(Reader::new(&[0x04, 0x05, 0x00]).read().is_err());
+        assert!(Reader::new(&[0x04, 0x80]).read().is_err());
+        assert!(Reader::new(&[0x04, 0x85, 0, 0, 0, 0, 1]).read().is_err());
+        assert!(Reader::new(&null()).expect(INTEGER).is_err());
+    }
+}
@@ -0,0 +1,491 @@
+mod ber;
+mod usm;
+
+use async_trait::async_trait;
+use rand::Rng;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use crate::{
+    models::monitor::Monitor,
+    monitor_conditions::{operators::STRING_OPERATORS, variables::ConditionVariable},
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, connect_udp, parse_config, json_query};
+use self::ber::Tlv;
+
+const GET_REQUEST: u8 = 0xA0;
+const GET_RESPONSE: u8 = 0xA2;
+const REPORT: u8 = 0xA8;
+
+const IP_ADDRESS: u8 = 0x40;
+const COUNTER32: u8 = 0x41;
+const GAUGE32: u8 = 0x42;
+const TIME_TICKS: u8 = 0x43;
+const OPAQUE: u8 = 0x44;
+const COUNTER64: u8 = 0x46;
+const NO_SUCH_OBJECT: u8 = 0x80;
+const NO_SUCH_INSTANCE: u8 = 0x81;
+const END_OF_MIB_VIEW: u8 = 0x82;
+
+/// Error status names from RFC 3416, indexed by status.
+const ERROR_STATUSES: &[&str] = &[
+    "noError", "tooBig", "noSuchName", "badValue", "readOnly", "genErr", "noAccess",
+    "wrongType", "wrongLength", "wrongEncoding", "wrongValue", "noCreation",
+    "inconsistentValue", "resourceUnavailable", "commitFailed", "undoFailed",
+    "authorizationError", "notWritable", "inconsistentName",
+];
+
+/// Largest UDP payload.
+const MAX_MESSAGE_SIZE: usize = 65507;
+
+const CONDITION_VARIABLES: &[ConditionVariable] = &[
+    ConditionVariable::new("value", STRING_OPERATORS),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+enum SnmpVersion {
+    #[serde(rename = "1")]
+    V1,
+    #[default]
+    #[serde(rename = "2c")]
+    V2c,
+    #[serde(rename = "3")]
+    V3,
+}
+
+#[derive(Debug, Deserialize)]
+struct SnmpConfig {
+    hostname: String,
+    #[serde(default = "default_port")]
+    port: u16,
+    #[serde(default)]
+    version: SnmpVersion,
+    /// Community string for v1 and v2c.
+    #[serde(default = "default_community")]
+    community: String,
+    /// User-based security settings for v3.
+    v3: Option<usm::UsmConfig>,
+    oid: String,
+    #[serde(default = "default_operator")]
+    operator: String,
+    expected_value: String,
+}
+
+fn default_port() -> u16 {
+    161
+}
+
+fn default_community() -> String {
+    "public".to_string()
+}
+
+fn default_operator() -> String {
+    "==".to_string()
+}
+
+/// Reads an OID from an SNMP agent and compares its value with an expected
+/// value.
+pub struct SnmpMonitorType;
+
+#[async_trait]
+impl MonitorType for SnmpMonitorType {
+    fn name(&self) -> &'static str {
+        "snmp"
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: SnmpConfig = parse_config(config)?;
+        if config.hostname.trim().is_empty() {
+            return Err(AppError::BadRequest("Hostname is required".to_string()));
+        }
+        if ber::parse_oid(&config.oid).is_none() {
+            return Err(AppError::BadRequest(format!("Invalid OID: {}", config.oid)));
+        }
+        if !json_query::OPERATORS.contains(&config.operator.as_str()) {
+            return Err(AppError::BadRequest(format!("Invalid operator: {}", config.operator)));
+        }
+        if config.version == SnmpVersion::V3 {
+            config
+                .v3
+                .as_ref()
+                .ok_or_else(|| AppError::BadRequest("SNMPv3 settings are required".to_string()))?
+                .validate()?;
+        }
+        Ok(())
+    }
+
+    fn condition_variables(&self) -> &'static [ConditionVariable] {
+        CONDITION_VARIABLES
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: SnmpConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid SNMP config"),
+        };
+        let Some(oid) = ber::parse_oid(&config.oid) else {
+            return CheckResult::down("Invalid SNMP config");
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let start_time = Instant::now();
+        let value = match tokio::time::timeout(timeout, get(&config, &oid)).await {
+            Ok(Ok(value)) => value,
+            Ok(Err(e)) => return CheckResult::down(format!("SNMP Error: {}", e)),
+            Err(_) => return CheckResult::down(format!("SNMP Error: No response after {}s", timeout.as_secs())),
+        };
+        let latency = start_time.elapsed().as_millis() as i32;
+
+        if value.is_exception() {
+            return CheckResult::down(format!(
+                "SNMP Error: The SNMP query returned that no instance exists for OID {}",
+                config.oid
+            ));
+        }
+
+        let value = value.to_string();
+        let result = match json_query::compare(&value, &config.operator, &config.expected_value) {
+            Ok(true) => CheckResult::up().with_message(format!(
+                "SNMP value passes (comparing {} {} {})",
+                value, config.operator, config.expected_value
+            )),
+            Ok(false) => CheckResult::down(format!(
+                "SNMP value does not pass (comparing {} {} {})",
+                value, config.operator, config.expected_value
+            )),
+            Err(e) => CheckResult::down(e),
+        };
+
+        result.with_latency(latency).with_variable("value", value)
+    }
+}
+
+/// Value of a variable binding.
+#[derive(Debug, Clone, PartialEq)]
+enum ObjectValue {
+    Integer(i64),
+    /// Counters, gauges and time ticks.
+    Unsigned(u64),
+    OctetString(Vec<u8>),
+    ObjectId(String),
+    IpAddress(Ipv4Addr),
+    Null,
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+impl ObjectValue {
+    fn parse(tlv: Tlv) -> Result<Self, String> {
+        let value = match tlv.tag {
+            ber::INTEGER => ObjectValue::Integer(ber::decode_integer(tlv.content)?),
+            COUNTER32 | GAUGE32 | TIME_TICKS | COUNTER64 => {
+                ObjectValue::Unsigned(ber::decode_unsigned(tlv.content)?)
+            }
+            ber::OCTET_STRING | OPAQUE => ObjectValue::OctetString(tlv.content.to_vec()),
+            ber::OBJECT_IDENTIFIER => ObjectValue::ObjectId(ber::format_oid(tlv.content)?),
+            IP_ADDRESS => {
+                let octets = <[u8; 4]>::try_from(tlv.content).map_err(|_| "Invalid IP address".to_string())?;
+                ObjectValue::IpAddress(Ipv4Addr::from(octets))
+            }
+            ber::NULL => ObjectValue::Null,
+            NO_SUCH_OBJECT => ObjectValue::NoSuchObject,
+            NO_SUCH_INSTANCE => ObjectValue::NoSuchInstance,
+            END_OF_MIB_VIEW => ObjectValue::EndOfMibView,
+            tag => return Err(format!("Unsupported value type 0x{:02x}", tag)),
+        };
+        Ok(value)
+    }
+
+    /// Whether the agent reported that the OID has no value.
+    fn is_exception(&self) -> bool {
+        matches!(
+            self,
+            ObjectValue::NoSuchObject | ObjectValue::NoSuchInstance | ObjectValue::EndOfMibView
+        )
+    }
+}
+
+impl fmt::Display for ObjectValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjectValue::Integer(value) => write!(f, "{}", value),
+            ObjectValue::Unsigned(value) => write!(f, "{}", value),
+            ObjectValue::OctetString(bytes) => match std::str::from_utf8(bytes) {
+                Ok(text) if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) => {
+                    write!(f, "{}", text)
+                }
+                // Binary values such as MAC addresses are shown in hex
+                _ => write!(f, "{}", bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")),
+            },
+            ObjectValue::ObjectId(oid) => write!(f, "{}", oid),
+            ObjectValue::IpAddress(ip) => write!(f, "{}", ip),
+            ObjectValue::Null => Ok(()),
+            ObjectValue::NoSuchObject => write!(f, "noSuchObject"),
+            ObjectValue::NoSuchInstance => write!(f, "noSuchInstance"),
+            ObjectValue::EndOfMibView => write!(f, "endOfMibView"),
+        }
+    }
+}
+
+/// A response or report PDU.
+#[derive(Debug)]
+struct Pdu {
+    tag: u8,
+    request_id: i64,
+    error_status: i64,
+    varbinds: Vec<(String, ObjectValue)>,
+}
+
+impl Pdu {
+    fn parse(tlv: Tlv) -> Result<Self, String> {
+        if tlv.tag != GET_RESPONSE && tlv.tag != REPORT {
+            return Err(format!("Unexpected PDU type 0x{:02x}", tlv.tag));
+        }
+
+        let mut reader = tlv.reader();
+        let request_id = reader.integer()?;
+        let error_status = reader.integer()?;
+        reader.integer()?; // error index
+
+        let mut list = reader.expect(ber::SEQUENCE)?.reader();
+        let mut varbinds = Vec::new();
+        while !list.is_empty() {
+            let mut varbind = list.expect(ber::SEQUENCE)?.reader();
+            let oid = ber::format_oid(varbind.expect(ber::OBJECT_IDENTIFIER)?.content)?;
+            varbinds.push((oid, ObjectValue::parse(varbind.read()?)?));
+        }
+
+        Ok(Self {
+            tag: tlv.tag,
+            request_id,
+            error_status,
+            varbinds,
+        })
+    }
+
+    /// Value of the single requested OID.
+    fn into_value(self, request_id: i32) -> Result<ObjectValue, String> {
+        if self.request_id != request_id as i64 {
+            return Err("Response does not match the request".to_string());
+        }
+        if self.error_status != 0 {
+            let status = usize::try_from(self.error_status)
+                .ok()
+                .and_then(|status| ERROR_STATUSES.get(status))
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("error status {}", self.error_status));
+            return Err(format!("Agent returned {}", status));
+        }
+        self.varbinds
+            .into_iter()
+            .next()
+            .map(|(_, value)| value)
+            .ok_or_else(|| "No varbinds returned".to_string())
+    }
+}
+
+/// Encodes a GetRequest PDU for one OID.
+fn get_request(request_id: i32, oid: &[u32]) -> Vec<u8> {
+    let varbind = ber::sequence(&[&ber::object_identifier(oid), &ber::null()]);
+    ber::constructed(
+        GET_REQUEST,
+        &[
+            &ber::integer(request_id as i64),
+            &ber::integer(0),
+            &ber::integer(0),
+            &ber::sequence(&[&varbind]),
+        ],
+    )
+}
+
+/// Positive random ID for requests and messages.
+fn random_id() -> i32 {
+    rand::thread_rng().gen_range(1..i32::MAX)
+}
+
+async fn send(socket: &UdpSocket, message: &[u8]) -> Result<Vec<u8>, String> {
+    socket.send(message).await.map_err(|e| e.to_string())?;
+    let mut buffer = vec![0u8; MAX_MESSAGE_SIZE];
+    let length = socket.recv(&mut buffer).await.map_err(|e| e.to_string())?;
+    buffer.truncate(length);
+    Ok(buffer)
+}
+
+async fn get(config: &SnmpConfig, oid: &[u32]) -> Result<ObjectValue, String> {
+    let socket = connect_udp(&config.hostname, config.port).await?;
+
+    match config.version {
+        SnmpVersion::V1 | SnmpVersion::V2c => community_get(&socket, config, oid).await,
+        SnmpVersion::V3 => {
+            let usm = config
+                .v3
+                .as_ref()
+                .ok_or_else(|| "SNMPv3 settings are required".to_string())?;
+            usm::get(&socket, usm, oid).await
+        }
+    }
+}
+
+/// Community based GetRequest for v1 and v2c.
+async fn community_get(socket: &UdpSocket, config: &SnmpConfig, oid: &[u32]) -> Result<ObjectValue, String> {
+    let version = if config.version == SnmpVersion::V1 { 0 } else { 1 };
+    let request_id = random_id();
+    let message = ber::sequence(&[
+        &ber::integer(version),
+        &ber::octet_string(config.community.as_bytes()),
+        &get_request(request_id, oid),
+    ]);
+
+    let response = send(socket, &message).await?;
+    read_community_response(version, request_id, &response)
+}
+
+/// Decodes the agent's answer to a v1 or v2c GetRequest.
+fn read_community_response(version: i64, request_id: i32, response: &[u8]) -> Result<ObjectValue, String> {
+    let mut message = ber::Reader::new(response).expect(ber::SEQUENCE)?.reader();
+    if message.integer()? != version {
+        return Err("Unexpected SNMP version in response".to_string());
+    }
+    message.octet_string()?; // community
+    Pdu::parse(message.read()?)?.into_value(request_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// v2c GetResponse to request 0x1234: sysUpTime.0 = 123456 time ticks.
+    const V2C_RESPONSE: &str = "302a02010104067075626c6963a21d020212340201000201003011300f06082b06010201010300430301e240";
+    /// v1 GetResponse to request 0x1234 with error status noSuchName.
+    const V1_ERROR_RESPONSE: &str = "302702010004067075626c6963a21a02021234020102020100300e300c06082b060102010103000500";
+
+    const SYS_UP_TIME: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 3, 0];
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn encodes_get_request() {
+        assert_eq!(
+            get_request(0x1234, SYS_UP_TIME),
+            hex("a01a02021234020100020100300e300c06082b060102010103000500")
+        );
+    }
+
+    #[test]
+    fn decodes_v2c_response() {
+        let response = hex(V2C_RESPONSE);
+        assert_eq!(read_community_response(1, 0x1234, &response), Ok(ObjectValue::Unsigned(123456)));
+        assert_eq!(
+            read_community_response(1, 0x1235, &response),
+            Err("Response does not match the request".to_string())
+        );
+        assert_eq!(
+            read_community_response(0, 0x1234, &response),
+            Err("Unexpected SNMP version in response".to_string())
+        );
+        assert!(read_community_response(1, 0x1234, &response[..20]).is_err());
+    }
+
+    #[test]
+    fn decodes_error_status() {
+        assert_eq!(
+            read_community_response(0, 0x1234, &hex(V1_ERROR_RESPONSE)),
+            Err("Agent returned noSuchName".to_string())
+        );
+    }
+
+    #[test]
+    fn formats_values() {
+        assert_eq!(ObjectValue::Integer(-5).to_string(), "-5");
+        assert_eq!(ObjectValue::OctetString(b"router1".to_vec()).to_string(), "router1");
+        assert_eq!(ObjectValue::OctetString(vec![0x00, 0x1A, 0x2B]).to_string(), "00 1A 2B");
+        assert_eq!(ObjectValue::IpAddress(Ipv4Addr::new(10, 0, 0, 1)).to_string(), "10.0.0.1");
+        assert!(ObjectValue::NoSuchInstance.is_exception());
+        assert!(!ObjectValue::Null.is_exception());
+
+        let ip = ber::encode(IP_ADDRESS, &[192, 168, 1, 3]);
+        let value = ObjectValue::parse(ber::Reader::new(&ip).read().unwrap()).unwrap();
+        assert_eq!(value, ObjectValue::IpAddress(Ipv4Addr::new(192, 168, 1, 3)));
+        let counter = ber::encode(COUNTER64, &[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        let value = ObjectValue::parse(ber::Reader::new(&counter).read().unwrap()).unwrap();
+        assert_eq!(value, ObjectValue::Unsigned(u64::MAX));
+    }
+
+    #[test]
+    fn validate_config() {
+        let snmp = SnmpMonitorType;
+        let config = |extra: Value| {
+            let mut config = json!({ "hostname": "switch.local", "oid": "1.3.6.1.2.1.1.3.0", "expected_value": "1" });
+            config.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            config
+        };
+        assert!(snmp.validate("", &config(json!({}))).is_ok());
+        assert!(snmp.validate("", &config(json!({ "oid": "1.3.x" }))).is_err());
+        assert!(snmp.validate("", &config(json!({ "operator": "~" }))).is_err());
+        assert!(snmp.validate("", &config(json!({ "version": "3" }))).is_err());
+        assert!(snmp
+            .validate("", &config(json!({ "version": "3", "v3": { "username": "monitor" } })))
+            .is_ok());
+    }
+
+    /// v1/v2c agent stand-in answering one GetRequest with `value`.
+    async fn agent(socket: UdpSocket, value: Vec<u8>) {
+        let mut buffer = vec![0u8; MAX_MESSAGE_SIZE];
+        let (length, peer) = socket.recv_from(&mut buffer).await.unwrap();
+        let mut message = ber::Reader::new(&buffer[..length]).expect(ber::SEQUENCE).unwrap().reader();
+        let version = message.integer().unwrap();
+        assert_eq!(message.octet_string().unwrap(), b"private");
+        let mut pdu = message.expect(GET_REQUEST).unwrap().reader();
+        let request_id = pdu.integer().unwrap();
+        pdu.integer().unwrap();
+        pdu.integer().unwrap();
+        let mut varbind = pdu.expect(ber::SEQUENCE).unwrap().reader().expect(ber::SEQUENCE).unwrap().reader();
+        let oid = varbind.expect(ber::OBJECT_IDENTIFIER).unwrap().content.to_vec();
+
+        let varbind = ber::sequence(&[&ber::encode(ber::OBJECT_IDENTIFIER, &oid), &value]);
+        let pdu = ber::constructed(
+            GET_RESPONSE,
+            &[&ber::integer(request_id), &ber::integer(0), &ber::integer(0), &ber::sequence(&[&varbind])],
+        );
+        let response = ber::sequence(&[&ber::integer(version), &ber::octet_string(b"private"), &pdu]);
+        socket.send_to(&response, peer).await.unwrap();
+    }
+
+    async fn get_from_agent(version: &str, value: Vec<u8>) -> Result<ObjectValue, String> {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let agent = tokio::spawn(agent(socket, value));
+
+        let config: SnmpConfig = parse_config(&json!({
+            "hostname": "127.0.0.1",
+            "port": port,
+            "version": version,
+            "community": "private",
+            "oid": "1.3.6.1.2.1.1.3.0",
+            "expected_value": "0",
+        }))
+        .unwrap();
+        let value = get(&config, SYS_UP_TIME).await;
+        agent.await.unwrap();
+        value
+    }
+
+    #[tokio::test]
+    async fn get_from_local_agent() {
+        let ticks = ber::encode(TIME_TICKS, &[0x01, 0xE2, 0x40]);
+        assert_eq!(get_from_agent("2c", ticks.clone()).await, Ok(ObjectValue::Unsigned(123456)));
+        assert_eq!(get_from_agent("1", ticks).await, Ok(ObjectValue::Unsigned(123456)));
+        assert_eq!(
+            get_from_agent("2c", ber::encode(NO_SUCH_INSTANCE, &[])).await,
+            Ok(ObjectValue::NoSuchInstance)
+        );
+    }
+}
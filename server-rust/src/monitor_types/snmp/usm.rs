@@ -0,0 +1,676 @@
+//! SNMPv3 user-based security model (RFC 3414), with the AES extension from
+//! RFC 3826.
+
+use aes::Aes128;
+use cbc::cipher::{block_padding::NoPadding, AsyncStreamCipher, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use des::Des;
+use hmac::{Hmac, Mac};
+use md5::{Digest, Md5};
+use serde::Deserialize;
+use sha1::Sha1;
+use tokio::net::UdpSocket;
+use crate::error::AppError;
+use super::{ber, ObjectValue, Pdu, MAX_MESSAGE_SIZE, REPORT, get_request, random_id, send};
+
+const VERSION: i64 = 3;
+const USM_SECURITY_MODEL: i64 = 3;
+
+const FLAG_AUTH: u8 = 0x01;
+const FLAG_PRIV: u8 = 0x02;
+const FLAG_REPORTABLE: u8 = 0x04;
+
+/// Length of the truncated HMAC sent in the authentication parameters.
+const AUTH_PARAMS_LENGTH: usize = 12;
+
+/// Counters an agent reports when it rejects a request.
+const USM_STATS: &[(&str, &str)] = &[
+    ("1.3.6.1.6.3.15.1.1.1.0", "Unsupported security level"),
+    ("1.3.6.1.6.3.15.1.1.2.0", "Not in time window"),
+    ("1.3.6.1.6.3.15.1.1.3.0", "Unknown user name"),
+    ("1.3.6.1.6.3.15.1.1.4.0", "Unknown engine ID"),
+    ("1.3.6.1.6.3.15.1.1.5.0", "Wrong digest, check the authentication password"),
+    ("1.3.6.1.6.3.15.1.1.6.0", "Decryption error, check the privacy password"),
+];
+
+/// Named after the RFC 3411 security levels.
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecurityLevel {
+    #[default]
+    NoAuthNoPriv,
+    AuthNoPriv,
+    AuthPriv,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthProtocol {
+    #[default]
+    Md5,
+    Sha,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivProtocol {
+    #[default]
+    Des,
+    Aes,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsmConfig {
+    username: String,
+    #[serde(default)]
+    security_level: SecurityLevel,
+    #[serde(default)]
+    auth_protocol: AuthProtocol,
+    #[serde(default)]
+    auth_password: String,
+    #[serde(default)]
+    priv_protocol: PrivProtocol,
+    #[serde(default)]
+    priv_password: String,
+}
+
+impl UsmConfig {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.username.is_empty() {
+            return Err(AppError::BadRequest("SNMPv3 username is required".to_string()));
+        }
+        // RFC 3414 requires passwords of at least 8 characters
+        if self.security_level != SecurityLevel::NoAuthNoPriv && self.auth_password.len() < 8 {
+            return Err(AppError::BadRequest(
+                "Authentication password must be at least 8 characters".to_string(),
+            ));
+        }
+        if self.security_level == SecurityLevel::AuthPriv && self.priv_password.len() < 8 {
+            return Err(AppError::BadRequest(
+                "Privacy password must be at least 8 characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn flags(&self) -> u8 {
+        match self.security_level {
+            SecurityLevel::NoAuthNoPriv => FLAG_REPORTABLE,
+            SecurityLevel::AuthNoPriv => FLAG_REPORTABLE | FLAG_AUTH,
+            SecurityLevel::AuthPriv => FLAG_REPORTABLE | FLAG_AUTH | FLAG_PRIV,
+        }
+    }
+}
+
+/// The msgSecurityParameters of a message.
+#[derive(Debug, Default)]
+struct SecurityParameters {
+    engine_id: Vec<u8>,
+    engine_boots: i64,
+    engine_time: i64,
+    username: Vec<u8>,
+    auth_params: Vec<u8>,
+    priv_params: Vec<u8>,
+}
+
+/// A decoded v3 message.
+struct Message<'a> {
+    id: i64,
+    flags: u8,
+    security: SecurityParameters,
+    /// Offset of the authentication parameters in the message.
+    auth_offset: usize,
+    /// Scoped PDU, encrypted if the privacy flag is set.
+    data: ber::Tlv<'a>,
+}
+
+impl<'a> Message<'a> {
+    fn encode(id: i32, flags: u8, security: &SecurityParameters, data: &[u8]) -> Vec<u8> {
+        let header = ber::sequence(&[
+            &ber::integer(id as i64),
+            &ber::integer(MAX_MESSAGE_SIZE as i64),
+            &ber::octet_string(&[flags]),
+            &ber::integer(USM_SECURITY_MODEL),
+        ]);
+        let security = ber::sequence(&[
+            &ber::octet_string(&security.engine_id),
+            &ber::integer(security.engine_boots),
+            &ber::integer(security.engine_time),
+            &ber::octet_string(&security.username),
+            &ber::octet_string(&security.auth_params),
+            &ber::octet_string(&security.priv_params),
+        ]);
+        ber::sequence(&[&ber::integer(VERSION), &header, &ber::octet_string(&security), data])
+    }
+
+    fn parse(data: &'a [u8]) -> Result<Self, String> {
+        let mut message = ber::Reader::new(data).expect(ber::SEQUENCE)?.reader();
+        if message.integer()? != VERSION {
+            return Err("Unexpected SNMP version in response".to_string());
+        }
+
+        let mut header = message.expect(ber::SEQUENCE)?.reader();
+        let id = header.integer()?;
+        header.integer()?; // max size
+        let flags = header.octet_string()?.first().copied().unwrap_or_default();
+        if header.integer()? != USM_SECURITY_MODEL {
+            return Err("Unsupported security model".to_string());
+        }
+
+        let mut params = message.expect(ber::OCTET_STRING)?.reader().expect(ber::SEQUENCE)?.reader();
+        let engine_id = params.octet_string()?.to_vec();
+        let engine_boots = params.integer()?;
+        let engine_time = params.integer()?;
+        let username = params.octet_string()?.to_vec();
+        let auth = params.expect(ber::OCTET_STRING)?;
+        let priv_params = params.octet_string()?.to_vec();
+
+        Ok(Self {
+            id,
+            flags,
+            security: SecurityParameters {
+                engine_id,
+                engine_boots,
+                engine_time,
+                username,
+                auth_params: auth.content.to_vec(),
+                priv_params,
+            },
+            auth_offset: auth.offset,
+            data: message.read()?,
+        })
+    }
+}
+
+/// Keys localized to the agent's engine ID.
+struct Keys {
+    auth: Vec<u8>,
+    privacy: Vec<u8>,
+}
+
+impl Keys {
+    fn new(config: &UsmConfig, engine_id: &[u8]) -> Result<Self, String> {
+        let localize = |password: &str| match config.auth_protocol {
+            AuthProtocol::Md5 => localize_key::<Md5>(password.as_bytes(), engine_id),
+            AuthProtocol::Sha => localize_key::<Sha1>(password.as_bytes(), engine_id),
+        };
+
+        let auth = match config.security_level {
+            SecurityLevel::NoAuthNoPriv => Vec::new(),
+            _ => localize(&config.auth_password).ok_or_else(|| "Authentication password is required".to_string())?,
+        };
+        let privacy = match config.security_level {
+            SecurityLevel::AuthPriv => {
+                localize(&config.priv_password).ok_or_else(|| "Privacy password is required".to_string())?
+            }
+            _ => Vec::new(),
+        };
+        Ok(Self { auth, privacy })
+    }
+}
+
+/// Password to key algorithm from RFC 3414 A.2: hash a megabyte of the
+/// repeated password, then localize the result with the engine ID.
+fn localize_key<D: Digest>(password: &[u8], engine_id: &[u8]) -> Option<Vec<u8>> {
+    if password.is_empty() {
+        return None;
+    }
+
+    let mut bytes = password.iter().cycle();
+    let mut hasher = D::new();
+    for _ in 0..(1_048_576 / 64) {
+        let block: [u8; 64] = std::array::from_fn(|_| *bytes.next().unwrap_or(&0));
+        hasher.update(block);
+    }
+    let key = hasher.finalize();
+
+    let mut hasher = D::new();
+    hasher.update(&key);
+    hasher.update(engine_id);
+    hasher.update(&key);
+    Some(hasher.finalize().to_vec())
+}
+
+/// HMAC-MD5-96 or HMAC-SHA-96 of a message whose authentication parameters
+/// are zeroed.
+fn authenticate(protocol: AuthProtocol, key: &[u8], message: &[u8]) -> Vec<u8> {
+    let digest = match protocol {
+        AuthProtocol::Md5 => {
+            let mut mac = Hmac::<Md5>::new_from_slice(key).expect("HMAC accepts keys of any length");
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+        AuthProtocol::Sha => {
+            let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+    };
+    digest[..AUTH_PARAMS_LENGTH].to_vec()
+}
+
+/// Encrypts a scoped PDU, returning the ciphertext and the salt to send as
+/// privacy parameters.
+fn encrypt(
+    protocol: PrivProtocol,
+    key: &[u8],
+    security: &SecurityParameters,
+    plaintext: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let invalid_key = |_| "Invalid privacy key".to_string();
+    match protocol {
+        PrivProtocol::Des => {
+            let salt = [
+                (security.engine_boots as u32).to_be_bytes(),
+                (random_id() as u32).to_be_bytes(),
+            ]
+            .concat();
+            let iv = des_iv(key, &salt)?;
+            // The padding is ignored by the agent, as the scoped PDU carries
+            // its own length
+            let mut buffer = plaintext.to_vec();
+            buffer.resize(plaintext.len().div_ceil(8) * 8, 0);
+            let length = buffer.len();
+            cbc::Encryptor::<Des>::new_from_slices(&key[..8], &iv)
+                .map_err(invalid_key)?
+                .encrypt_padded_mut::<NoPadding>(&mut buffer, length)
+                .map_err(|_| "Cannot encrypt scoped PDU".to_string())?;
+            Ok((buffer, salt))
+        }
+        PrivProtocol::Aes => {
+            let salt = rand::random::<u64>().to_be_bytes().to_vec();
+            let iv = aes_iv(security, &salt);
+            let mut buffer = plaintext.to_vec();
+            cfb_mode::Encryptor::<Aes128>::new_from_slices(&key[..16], &iv)
+                .map_err(invalid_key)?
+                .encrypt(&mut buffer);
+            Ok((buffer, salt))
+        }
+    }
+}
+
+fn decrypt(
+    protocol: PrivProtocol,
+    key: &[u8],
+    security: &SecurityParameters,
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, String> {
+    let invalid_key = |_| "Invalid privacy key".to_string();
+    let mut buffer = ciphertext.to_vec();
+    match protocol {
+        PrivProtocol::Des => {
+            if !buffer.len().is_multiple_of(8) {
+                return Err("Invalid encrypted PDU length".to_string());
+            }
+            let iv = des_iv(key, &security.priv_params)?;
+            cbc::Decryptor::<Des>::new_from_slices(&key[..8], &iv)
+                .map_err(invalid_key)?
+                .decrypt_padded_mut::<NoPadding>(&mut buffer)
+                .map_err(|_| "Cannot decrypt scoped PDU".to_string())?;
+        }
+        PrivProtocol::Aes => {
+            if security.priv_params.len() != 8 {
+                return Err("Invalid privacy parameters".to_string());
+            }
+            let iv = aes_iv(security, &security.priv_params);
+            cfb_mode::Decryptor::<Aes128>::new_from_slices(&key[..16], &iv)
+                .map_err(invalid_key)?
+                .decrypt(&mut buffer);
+        }
+    }
+    Ok(buffer)
+}
+
+/// DES-CBC IV: the pre-IV half of the privacy key XORed with the salt.
+fn des_iv(key: &[u8], salt: &[u8]) -> Result<Vec<u8>, String> {
+    if key.len() < 16 || salt.len() != 8 {
+        return Err("Invalid privacy parameters".to_string());
+    }
+    Ok(key[8..16].iter().zip(salt).map(|(k, s)| k ^ s).collect())
+}
+
+/// AES-CFB IV: engine boots and time followed by the salt.
+fn aes_iv(security: &SecurityParameters, salt: &[u8]) -> Vec<u8> {
+    [
+        &(security.engine_boots as u32).to_be_bytes()[..],
+        &(security.engine_time as u32).to_be_bytes()[..],
+        salt,
+    ]
+    .concat()
+}
+
+/// Reads the agent's engine ID, boots and time from the report it sends in
+/// reply to an unauthenticated request.
+async fn discover(socket: &UdpSocket, oid: &[u32]) -> Result<SecurityParameters, String> {
+    let scoped_pdu = ber::sequence(&[&ber::octet_string(&[]), &ber::octet_string(&[]), &get_request(random_id(), oid)]);
+    let request = Message::encode(random_id(), FLAG_REPORTABLE, &SecurityParameters::default(), &scoped_pdu);
+
+    let response = send(socket, &request).await?;
+    let security = Message::parse(&response)?.security;
+    if security.engine_id.is_empty() {
+        return Err("Agent did not report its engine ID".to_string());
+    }
+    Ok(security)
+}
+
+/// Explains why the agent rejected a request.
+fn report_error(pdu: &Pdu) -> String {
+    let reason = pdu.varbinds.first().and_then(|(oid, _)| {
+        USM_STATS
+            .iter()
+            .find(|(counter, _)| counter == oid)
+            .map(|(_, reason)| reason.to_string())
+    });
+    reason.unwrap_or_else(|| "Request rejected by agent".to_string())
+}
+
+/// Authenticated (and optionally encrypted) GetRequest for one OID.
+pub async fn get(socket: &UdpSocket, config: &UsmConfig, oid: &[u32]) -> Result<ObjectValue, String> {
+    let engine = discover(socket, oid).await?;
+    let keys = Keys::new(config, &engine.engine_id)?;
+    let flags = config.flags();
+
+    let request_id = random_id();
+    let scoped_pdu = ber::sequence(&[
+        &ber::octet_string(&engine.engine_id),
+        &ber::octet_string(&[]),
+        &get_request(request_id, oid),
+    ]);
+
+    let mut security = SecurityParameters {
+        username: config.username.as_bytes().to_vec(),
+        ..engine
+    };
+    security.auth_params.clear();
+    security.priv_params.clear();
+
+    let data = if flags & FLAG_PRIV != 0 {
+        let (ciphertext, salt) = encrypt(config.priv_protocol, &keys.privacy, &security, &scoped_pdu)?;
+        security.priv_params = salt;
+        ber::octet_string(&ciphertext)
+    } else {
+        scoped_pdu
+    };
+    if flags & FLAG_AUTH != 0 {
+        security.auth_params = vec![0; AUTH_PARAMS_LENGTH];
+    }
+
+    let message_id = random_id();
+    let mut request = Message::encode(message_id, flags, &security, &data);
+    if flags & FLAG_AUTH != 0 {
+        let offset = Message::parse(&request)?.auth_offset;
+        let digest = authenticate(config.auth_protocol, &keys.auth, &request);
+        request[offset..offset + AUTH_PARAMS_LENGTH].copy_from_slice(&digest);
+    }
+
+    let response = send(socket, &request).await?;
+    read_response(config, &keys, flags, message_id, request_id, &response)
+}
+
+/// Verifies, decrypts and decodes the agent's answer to a request.
+fn read_response(
+    config: &UsmConfig,
+    keys: &Keys,
+    flags: u8,
+    message_id: i32,
+    request_id: i32,
+    response: &[u8],
+) -> Result<ObjectValue, String> {
+    let message = Message::parse(response)?;
+    if message.id != message_id as i64 {
+        return Err("Response does not match the request".to_string());
+    }
+
+    if message.flags & FLAG_AUTH != 0 {
+        if message.security.auth_params.len() != AUTH_PARAMS_LENGTH {
+            return Err("Invalid authentication parameters in response".to_string());
+        }
+        let mut unsigned = response.to_vec();
+        unsigned[message.auth_offset..message.auth_offset + AUTH_PARAMS_LENGTH].fill(0);
+        if authenticate(config.auth_protocol, &keys.auth, &unsigned) != message.security.auth_params {
+            return Err("Response failed authentication".to_string());
+        }
+    }
+
+    let plaintext;
+    let scoped_pdu = if message.flags & FLAG_PRIV != 0 {
+        if message.data.tag != ber::OCTET_STRING {
+            return Err("Expected an encrypted scoped PDU".to_string());
+        }
+        plaintext = decrypt(config.priv_protocol, &keys.privacy, &message.security, message.data.content)?;
+        ber::Reader::new(&plaintext).expect(ber::SEQUENCE)?
+    } else if message.data.tag == ber::SEQUENCE {
+        message.data
+    } else {
+        return Err("Expected a scoped PDU".to_string());
+    };
+
+    let mut scoped_pdu = scoped_pdu.reader();
+    scoped_pdu.octet_string()?; // context engine ID
+    scoped_pdu.octet_string()?; // context name
+    let pdu = Pdu::parse(scoped_pdu.read()?)?;
+
+    if pdu.tag == REPORT {
+        return Err(report_error(&pdu));
+    }
+    // Only reports may skip the security level of the request
+    if message.flags & (FLAG_AUTH | FLAG_PRIV) != flags & (FLAG_AUTH | FLAG_PRIV) {
+        return Err("Response security level does not match the request".to_string());
+    }
+    pdu.into_value(request_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{GET_REQUEST, GET_RESPONSE};
+    use crate::monitor_types::connect_udp;
+    use serde_json::json;
+
+    const ENGINE_ID: &str = "80001f888059dc486145a26322";
+    /// Answer to discovery: usmStatsUnknownEngineIDs, engine boots 5 and
+    /// time 1234.
+    const DISCOVERY_REPORT: &str = "3065020103300f020203e9020300ffe3040100020103041e301c040d80001f888059dc486145a26322020105020204d2040004000400302f040d80001f888059dc486145a263220400a81c0201000201000201003011300f060a2b060106030f01010400410107";
+    /// GetResponse to message 99, request 77: sysName.0 = "router1" for user
+    /// "monitor", signed with HMAC-MD5-96 and encrypted with AES-128-CFB.
+    const AUTH_PRIV_RESPONSE: &str = "308185020103300e020163020300ffe304010302010304393037040d80001f888059dc486145a26322020105020204d204076d6f6e69746f72040ca7366424e916109e0ae8a301040801020304050607080435face52ba50ba6e35d7a90a2c36ef4c777ee95ef1a9dc9837d94353f7c4a776409036a70c5b9d9646759bbde8bc3377c17722475326";
+    /// Unauthenticated usmStatsWrongDigests report to message 99.
+    const WRONG_DIGEST_REPORT: &str = "306b020103300e020163020300ffe304010002010304253023040d80001f888059dc486145a26322020105020204d204076d6f6e69746f7204000400302f040d80001f888059dc486145a263220400a81c02014d0201000201003011300f060a2b060106030f01010500410101";
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    fn auth_priv_config() -> UsmConfig {
+        serde_json::from_value(json!({
+            "username": "monitor",
+            "security_level": "authPriv",
+            "auth_protocol": "md5",
+            "auth_password": "authpass123",
+            "priv_protocol": "aes",
+            "priv_password": "privpass123",
+        }))
+        .unwrap()
+    }
+
+    fn engine() -> SecurityParameters {
+        SecurityParameters {
+            engine_id: hex(ENGINE_ID),
+            engine_boots: 5,
+            engine_time: 1234,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn localize_key_rfc3414_vectors() {
+        // RFC 3414, appendix A.3
+        let engine_id = hex("000000000000000000000002");
+        assert_eq!(
+            localize_key::<Md5>(b"maplesyrup", &engine_id).unwrap(),
+            hex("526f5eed9fcce26f8964c2930787d82b")
+        );
+        assert_eq!(
+            localize_key::<Sha1>(b"maplesyrup", &engine_id).unwrap(),
+            hex("6695febc9288e36282235fc7151f128497b38f3f")
+        );
+        assert_eq!(localize_key::<Md5>(b"", &engine_id), None);
+    }
+
+    #[test]
+    fn validate_passwords() {
+        assert!(auth_priv_config().validate().is_ok());
+        let short: UsmConfig = serde_json::from_value(json!({
+            "username": "monitor",
+            "security_level": "authNoPriv",
+            "auth_password": "short",
+        }))
+        .unwrap();
+        assert!(short.validate().is_err());
+        let no_priv: UsmConfig = serde_json::from_value(json!({
+            "username": "monitor",
+            "security_level": "authPriv",
+            "auth_password": "authpass123",
+        }))
+        .unwrap();
+        assert!(no_priv.validate().is_err());
+    }
+
+    #[test]
+    fn encryption_round_trip() {
+        let key = localize_key::<Md5>(b"privpass123", &hex(ENGINE_ID)).unwrap();
+        let plaintext = ber::sequence(&[&ber::octet_string(b"scoped"), &ber::integer(77)]);
+        for protocol in [PrivProtocol::Des, PrivProtocol::Aes] {
+            let mut security = engine();
+            let (ciphertext, salt) = encrypt(protocol, &key, &security, &plaintext).unwrap();
+            assert_eq!(salt.len(), 8);
+            assert_ne!(ciphertext[..plaintext.len()], plaintext[..]);
+
+            security.priv_params = salt;
+            let decrypted = decrypt(protocol, &key, &security, &ciphertext).unwrap();
+            assert_eq!(decrypted[..plaintext.len()], plaintext[..], "{:?}", protocol);
+        }
+        assert!(decrypt(PrivProtocol::Des, &key, &engine(), &[0; 7]).is_err());
+        assert!(decrypt(PrivProtocol::Aes, &key, &engine(), &[0; 16]).is_err());
+    }
+
+    #[test]
+    fn decodes_discovery_report() {
+        let response = hex(DISCOVERY_REPORT);
+        let message = Message::parse(&response).unwrap();
+        assert_eq!(message.flags, 0);
+        assert_eq!(message.security.engine_id, hex(ENGINE_ID));
+        assert_eq!(message.security.engine_boots, 5);
+        assert_eq!(message.security.engine_time, 1234);
+
+        let mut scoped_pdu = message.data.reader();
+        scoped_pdu.octet_string().unwrap();
+        scoped_pdu.octet_string().unwrap();
+        let pdu = Pdu::parse(scoped_pdu.read().unwrap()).unwrap();
+        assert_eq!(pdu.tag, REPORT);
+        assert_eq!(report_error(&pdu), "Unknown engine ID");
+    }
+
+    #[test]
+    fn decodes_auth_priv_response() {
+        let config = auth_priv_config();
+        let keys = Keys::new(&config, &hex(ENGINE_ID)).unwrap();
+        let response = hex(AUTH_PRIV_RESPONSE);
+        assert_eq!(
+            read_response(&config, &keys, config.flags(), 99, 77, &response),
+            Ok(ObjectValue::OctetString(b"router1".to_vec()))
+        );
+        assert!(read_response(&config, &keys, config.flags(), 98, 77, &response).is_err());
+        assert!(read_response(&config, &keys, config.flags(), 99, 78, &response).is_err());
+
+        let mut tampered = response.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            read_response(&config, &keys, config.flags(), 99, 77, &tampered),
+            Err("Response failed authentication".to_string())
+        );
+
+        let wrong_keys = Keys::new(
+            &UsmConfig {
+                auth_password: "wrongpass123".to_string(),
+                ..auth_priv_config()
+            },
+            &hex(ENGINE_ID),
+        )
+        .unwrap();
+        assert_eq!(
+            read_response(&config, &wrong_keys, config.flags(), 99, 77, &response),
+            Err("Response failed authentication".to_string())
+        );
+    }
+
+    #[test]
+    fn decodes_wrong_digest_report() {
+        let config = auth_priv_config();
+        let keys = Keys::new(&config, &hex(ENGINE_ID)).unwrap();
+        assert_eq!(
+            read_response(&config, &keys, config.flags(), 99, 77, &hex(WRONG_DIGEST_REPORT)),
+            Err("Wrong digest, check the authentication password".to_string())
+        );
+    }
+
+    /// SNMPv3 agent stand-in: answers discovery, then checks and answers one
+    /// authPriv GetRequest for sysName.0.
+    async fn agent(socket: UdpSocket) {
+        let mut buffer = vec![0u8; MAX_MESSAGE_SIZE];
+        let (_, peer) = socket.recv_from(&mut buffer).await.unwrap();
+        socket.send_to(&hex(DISCOVERY_REPORT), peer).await.unwrap();
+
+        let (length, peer) = socket.recv_from(&mut buffer).await.unwrap();
+        let config = auth_priv_config();
+        let keys = Keys::new(&config, &hex(ENGINE_ID)).unwrap();
+        let request = Message::parse(&buffer[..length]).unwrap();
+        assert_eq!(request.flags, FLAG_REPORTABLE | FLAG_AUTH | FLAG_PRIV);
+        assert_eq!(request.security.username, b"monitor");
+        let mut unsigned = buffer[..length].to_vec();
+        unsigned[request.auth_offset..request.auth_offset + AUTH_PARAMS_LENGTH].fill(0);
+        assert_eq!(authenticate(AuthProtocol::Md5, &keys.auth, &unsigned), request.security.auth_params);
+
+        let scoped_pdu = decrypt(PrivProtocol::Aes, &keys.privacy, &request.security, request.data.content).unwrap();
+        let mut scoped_pdu = ber::Reader::new(&scoped_pdu).expect(ber::SEQUENCE).unwrap().reader();
+        assert_eq!(scoped_pdu.octet_string().unwrap(), hex(ENGINE_ID));
+        scoped_pdu.octet_string().unwrap();
+        let request_id = scoped_pdu.expect(GET_REQUEST).unwrap().reader().integer().unwrap();
+
+        let varbind = ber::sequence(&[&ber::object_identifier(&[1, 3, 6, 1, 2, 1, 1, 5, 0]), &ber::octet_string(b"router1")]);
+        let pdu = ber::constructed(
+            GET_RESPONSE,
+            &[&ber::integer(request_id), &ber::integer(0), &ber::integer(0), &ber::sequence(&[&varbind])],
+        );
+        let scoped_pdu = ber::sequence(&[&ber::octet_string(&hex(ENGINE_ID)), &ber::octet_string(&[]), &pdu]);
+        let mut security = SecurityParameters {
+            username: b"monitor".to_vec(),
+            auth_params: vec![0; AUTH_PARAMS_LENGTH],
+            ..engine()
+        };
+        let (ciphertext, salt) = encrypt(PrivProtocol::Aes, &keys.privacy, &security, &scoped_pdu).unwrap();
+        security.priv_params = salt;
+        let mut response = Message::encode(
+            request.id as i32,
+            FLAG_AUTH | FLAG_PRIV,
+            &security,
+            &ber::octet_string(&ciphertext),
+        );
+        let offset = Message::parse(&response).unwrap().auth_offset;
+        let digest = authenticate(AuthProtocol::Md5, &keys.auth, &response);
+        response[offset..offset + AUTH_PARAMS_LENGTH].copy_from_slice(&digest);
+        socket.send_to(&response, peer).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn get_from_local_agent() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let agent = tokio::spawn(agent(socket));
+
+        let socket = connect_udp("127.0.0.1", port).await.unwrap();
+        let value = get(&socket, &auth_priv_config(), &[1, 3, 6, 1, 2, 1, 1, 5, 0]).await;
+        assert_eq!(value, Ok(ObjectValue::OctetString(b"router1".to_vec())));
+        agent.await.unwrap();
+    }
+}
@@ -0,0 +1,341 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use crate::{
+    models::monitor::Monitor,
+    monitor_conditions::{
+        operators::{NUMBER_OPERATORS, STRING_OPERATORS},
+        variables::ConditionVariable,
+    },
+    error::AppError,
+};
+use super::{CheckResult, MonitorType, connect_udp, parse_config};
+
+const SIMPLE_HEADER: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+const SPLIT_HEADER: [u8; 4] = [0xFE, 0xFF, 0xFF, 0xFF];
+const A2S_INFO: &[u8] = b"\x54Source Engine Query\0";
+const S2C_CHALLENGE: u8 = 0x41;
+const INFO_RESPONSE: u8 = 0x49;
+const GOLDSOURCE_INFO_RESPONSE: u8 = 0x6D;
+
+/// Gamedig game ids whose servers answer A2S_INFO, the only protocol
+/// implemented here.
+const SOURCE_GAMES: &[&str] = &[
+    "arma3", "cs16", "csgo", "css", "dayz", "garrysmod", "hl2dm", "left4dead", "left4dead2", "rust",
+    "tf2", "valheim",
+];
+
+const CONDITION_VARIABLES: &[ConditionVariable] = &[
+    ConditionVariable::new("map", STRING_OPERATORS),
+    ConditionVariable::new("players", NUMBER_OPERATORS),
+    ConditionVariable::new("max_players", NUMBER_OPERATORS),
+];
+
+#[derive(Debug, Deserialize)]
+struct SteamConfig {
+    hostname: String,
+    #[serde(default = "default_port")]
+    port: u16,
+    /// Gamedig game id, required by the "gamedig" type.
+    game: Option<String>,
+}
+
+fn default_port() -> u16 {
+    27015
+}
+
+/// Server details from an A2S_INFO response.
+#[derive(Debug, Default, Serialize)]
+struct ServerInfo {
+    name: String,
+    map: String,
+    game: String,
+    players: u8,
+    max_players: u8,
+    bots: u8,
+}
+
+/// Queries a game server with the Steam A2S_INFO protocol.
+///
+/// Registered as "steam" and "gamedig"; the latter only accepts games
+/// listed in `SOURCE_GAMES`.
+pub struct SteamMonitorType {
+    name: &'static str,
+}
+
+impl SteamMonitorType {
+    pub fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+#[async_trait]
+impl MonitorType for SteamMonitorType {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn validate(&self, _url: &str, config: &Value) -> Result<(), AppError> {
+        let config: SteamConfig = parse_config(config)?;
+        if config.hostname.trim().is_empty() {
+            return Err(AppError::BadRequest("Hostname is required".to_string()));
+        }
+        if self.name == "gamedig" {
+            let game = config.game.as_deref().unwrap_or_default();
+            if game.is_empty() {
+                return Err(AppError::BadRequest("Game is required".to_string()));
+            }
+            if !SOURCE_GAMES.contains(&game) {
+                return Err(AppError::BadRequest(format!(
+                    "Unsupported game: {}, only Source engine games are supported",
+                    game
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn condition_variables(&self) -> &'static [ConditionVariable] {
+        CONDITION_VARIABLES
+    }
+
+    async fn check(&self, monitor: &Monitor) -> CheckResult {
+        let config: SteamConfig = match parse_config(&monitor.config) {
+            Ok(config) => config,
+            Err(_) => return CheckResult::down("Invalid game server config"),
+        };
+
+        let timeout = Duration::from_secs(monitor.timeout.max(1) as u64);
+        let start_time = Instant::now();
+        let info = match tokio::time::timeout(timeout, query_info(&config)).await {
+            Ok(Ok(info)) => info,
+            Ok(Err(e)) => return CheckResult::down(e),
+            Err(_) => return CheckResult::down(format!("No response after {}s", timeout.as_secs())),
+        };
+        let latency = start_time.elapsed().as_millis() as i32;
+
+        let message = format!(
+            "{} - Map: {}, Players: {}/{}",
+            info.name, info.map, info.players, info.max_players
+        );
+        CheckResult::up()
+            .with_message(message)
+            .with_latency(latency)
+            .with_variable("map", info.map.clone())
+            .with_variable("players", info.players)
+            .with_variable("max_players", info.max_players)
+            .with_details(serde_json::to_value(&info).unwrap_or(Value::Null))
+    }
+}
+
+/// Sends A2S_INFO, answering a challenge if the server asks for one.
+async fn query_info(config: &SteamConfig) -> Result<ServerInfo, String> {
+    let socket = connect_udp(&config.hostname, config.port).await?;
+
+    let mut request = [&SIMPLE_HEADER[..], A2S_INFO].concat();
+    let mut buffer = [0u8; 1400];
+    // The first reply may be a challenge, the second must be the info
+    for _ in 0..2 {
+        socket.send(&request).await.map_err(|e| e.to_string())?;
+        let length = socket.recv(&mut buffer).await.map_err(|e| e.to_string())?;
+        let response = &buffer[..length];
+
+        if response.starts_with(&SPLIT_HEADER) {
+            return Err("Split responses are not supported".to_string());
+        }
+        let Some(payload) = response.strip_prefix(&SIMPLE_HEADER) else {
+            return Err("Invalid response".to_string());
+        };
+
+        match payload.first() {
+            Some(&S2C_CHALLENGE) if payload.len() >= 5 => {
+                request.truncate(SIMPLE_HEADER.len() + A2S_INFO.len());
+                request.extend_from_slice(&payload[1..5]);
+            }
+            Some(&INFO_RESPONSE) => return parse_info(&payload[1..]),
+            Some(&GOLDSOURCE_INFO_RESPONSE) => return parse_goldsource_info(&payload[1..]),
+            _ => return Err("Unexpected response".to_string()),
+        }
+    }
+
+    Err("Server kept sending challenges".to_string())
+}
+
+/// Parses a Source engine info response, after the header byte.
+fn parse_info(data: &[u8]) -> Result<ServerInfo, String> {
+    let mut reader = Reader(data);
+    reader.byte()?; // protocol
+    let name = reader.string()?;
+    let map = reader.string()?;
+    reader.string()?; // folder
+    let game = reader.string()?;
+    reader.bytes(2)?; // app id
+    Ok(ServerInfo {
+        name,
+        map,
+        game,
+        players: reader.byte()?,
+        max_players: reader.byte()?,
+        bots: reader.byte()?,
+    })
+}
+
+/// Parses the obsolete GoldSource info response, after the header byte.
+fn parse_goldsource_info(data: &[u8]) -> Result<ServerInfo, String> {
+    let mut reader = Reader(data);
+    reader.string()?; // address
+    let name = reader.string()?;
+    let map = reader.string()?;
+    reader.string()?; // folder
+    let game = reader.string()?;
+    Ok(ServerInfo {
+        name,
+        map,
+        game,
+        players: reader.byte()?,
+        max_players: reader.byte()?,
+        bots: 0,
+    })
+}
+
+/// Reads the fields of a response in order.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.0.len() < count {
+            return Err("Truncated response".to_string());
+        }
+        let (bytes, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    /// Null terminated string.
+    fn string(&mut self) -> Result<String, String> {
+        let end = self
+            .0
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| "Truncated response".to_string())?;
+        let value = String::from_utf8_lossy(&self.0[..end]).into_owned();
+        self.0 = &self.0[end + 1..];
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::net::UdpSocket;
+
+    const CHALLENGE: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+
+    /// Source info response as sent by a TF2 server, without the header.
+    fn source_info() -> Vec<u8> {
+        let mut data = vec![INFO_RESPONSE, 17];
+        data.extend_from_slice(b"Community TF2\0ctf_2fort\0tf\0Team Fortress\0");
+        data.extend_from_slice(&440u16.to_le_bytes());
+        data.extend_from_slice(&[12, 24, 2]);
+        data.extend_from_slice(b"dl\0\x01\x01");
+        data
+    }
+
+    fn goldsource_info() -> Vec<u8> {
+        let mut data = vec![GOLDSOURCE_INFO_RESPONSE];
+        data.extend_from_slice(b"127.0.0.1:27015\0Old School\0de_dust2\0cstrike\0Counter-Strike\0");
+        data.extend_from_slice(&[5, 32, 47]);
+        data
+    }
+
+    #[test]
+    fn parses_source_info() {
+        let info = parse_info(&source_info()[1..]).unwrap();
+        assert_eq!(info.name, "Community TF2");
+        assert_eq!(info.map, "ctf_2fort");
+        assert_eq!(info.game, "Team Fortress");
+        assert_eq!((info.players, info.max_players, info.bots), (12, 24, 2));
+    }
+
+    #[test]
+    fn parses_goldsource_info() {
+        let info = parse_goldsource_info(&goldsource_info()[1..]).unwrap();
+        assert_eq!(info.name, "Old School");
+        assert_eq!(info.map, "de_dust2");
+        assert_eq!(info.game, "Counter-Strike");
+        assert_eq!((info.players, info.max_players, info.bots), (5, 32, 0));
+    }
+
+    #[test]
+    fn rejects_truncated_info() {
+        let data = source_info();
+        assert!(parse_info(&data[1..data.len() - 10]).is_err());
+        assert!(parse_info(&data[1..20]).is_err());
+        assert!(parse_goldsource_info(b"127.0.0.1:27015\0Old").is_err());
+    }
+
+    #[test]
+    fn gamedig_requires_source_game() {
+        let steam = SteamMonitorType::new("steam");
+        let gamedig = SteamMonitorType::new("gamedig");
+        assert!(steam.validate("", &json!({ "hostname": "game.local" })).is_ok());
+        assert!(steam.validate("", &json!({ "hostname": "" })).is_err());
+        assert!(gamedig.validate("", &json!({ "hostname": "game.local" })).is_err());
+        assert!(gamedig.validate("", &json!({ "hostname": "game.local", "game": "minecraft" })).is_err());
+        assert!(gamedig.validate("", &json!({ "hostname": "game.local", "game": "tf2" })).is_ok());
+    }
+
+    /// Game server stand-in that asks for a challenge before answering with
+    /// `info`, returning the requests it received.
+    async fn serve(socket: UdpSocket, info: Vec<u8>) -> Vec<Vec<u8>> {
+        let mut requests = Vec::new();
+        let mut buffer = [0u8; 1400];
+        loop {
+            let (length, peer) = socket.recv_from(&mut buffer).await.unwrap();
+            let request = buffer[..length].to_vec();
+            let answered = request.ends_with(&CHALLENGE);
+            requests.push(request);
+
+            let payload = if answered {
+                info.clone()
+            } else {
+                [&[S2C_CHALLENGE][..], &CHALLENGE].concat()
+            };
+            socket.send_to(&[&SIMPLE_HEADER[..], &payload].concat(), peer).await.unwrap();
+            if answered {
+                return requests;
+            }
+        }
+    }
+
+    async fn query(info: Vec<u8>) -> (Result<ServerInfo, String>, Vec<Vec<u8>>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let server = tokio::spawn(serve(socket, info));
+
+        let config: SteamConfig = parse_config(&json!({ "hostname": "127.0.0.1", "port": port })).unwrap();
+        let result = query_info(&config).await;
+        (result, server.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn answers_challenge() {
+        let (info, requests) = query(source_info()).await;
+        assert_eq!(info.unwrap().map, "ctf_2fort");
+
+        let request = [&SIMPLE_HEADER[..], A2S_INFO].concat();
+        assert_eq!(requests, vec![request.clone(), [&request[..], &CHALLENGE].concat()]);
+    }
+
+    #[tokio::test]
+    async fn accepts_goldsource_response() {
+        let (info, _) = query(goldsource_info()).await;
+        assert_eq!(info.unwrap().name, "Old School");
+    }
+}
@@ -0,0 +1,251 @@
+use chrono::{DateTime, TimeZone, Utc};
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
+    Certificate, RootCertStore, ServerName,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use x509_parser::{extensions::GeneralName, prelude::*};
+use crate::{
+    models::{
+        monitor::Monitor,
+        notification::Notification,
+        tls_info::{CertificateInfo, TlsInfo},
+    },
+    services::notification::NotificationService,
+    error::AppError,
+};
+use super::parse_config;
+
+const NOTIFICATION_TYPE: &str = "certificate";
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct ExpiryConfig {
+    /// Send notifications when a certificate is about to expire.
+    expiry_notification: bool,
+    /// Days before expiry at which a notification is sent.
+    tls_expiry_notify_days: Vec<i64>,
+}
+
+impl Default for ExpiryConfig {
+    fn default() -> Self {
+        Self {
+            expiry_notification: false,
+            tls_expiry_notify_days: vec![7, 14, 21],
+        }
+    }
+}
+
+/// Records the certificate chain of HTTPS monitors and warns before it expires.
+pub struct TlsInspector {
+    pool: SqlitePool,
+    notifications: Arc<NotificationService>,
+}
+
+impl TlsInspector {
+    pub fn new(pool: SqlitePool, notifications: Arc<NotificationService>) -> Self {
+        Self { pool, notifications }
+    }
+
+    /// Stores the certificate chain seen on the monitor's connection, then
+    /// sends any due expiry notifications.
+    pub async fn inspect(&self, monitor: &Monitor, info: &TlsInfo) {
+        if let Err(e) = self.store(monitor, info).await {
+            tracing::error!("Cannot save TLS info for monitor #{}: {}", monitor.id, e);
+        }
+
+        if let Err(e) = self.check_expiry(monitor, info).await {
+            tracing::error!("Cannot send certificate notification for monitor #{}: {:?}", monitor.id, e);
+        }
+    }
+
+    async fn store(&self, monitor: &Monitor, info: &TlsInfo) -> Result<(), sqlx::Error> {
+        let previous = TlsInfo::find_by_monitor(&self.pool, monitor.id).await?;
+
+        // A new certificate restarts the expiry notifications
+        let renewed = previous
+            .as_ref()
+            .and_then(|previous| previous.leaf())
+            .zip(info.leaf())
+            .is_some_and(|(old, new)| old.serial != new.serial);
+        if renewed {
+            Notification::clear_sent(&self.pool, NOTIFICATION_TYPE, monitor.id).await?;
+        }
+
+        info.save(&self.pool, monitor.id).await
+    }
+
+    async fn check_expiry(&self, monitor: &Monitor, info: &TlsInfo) -> Result<(), AppError> {
+        let config: ExpiryConfig = parse_config(&monitor.config)?;
+        if !config.expiry_notification {
+            return Ok(());
+        }
+
+        for &target_days in &config.tls_expiry_notify_days {
+            for cert in info.chain.iter().filter(|cert| cert.cert_type != "root CA") {
+                if cert.days_remaining > target_days {
+                    continue;
+                }
+                if Notification::was_sent(&self.pool, NOTIFICATION_TYPE, monitor.id, target_days).await? {
+                    continue;
+                }
+
+                let message = format!(
+                    "[{}][{}] {} certificate {} will expire in {} days",
+                    monitor.name,
+                    monitor.url,
+                    cert.cert_type,
+                    cert.common_name.as_deref().unwrap_or(&cert.subject),
+                    cert.days_remaining
+                );
+                if self.notifications.send(monitor, &message).await? {
+                    Notification::mark_sent(&self.pool, NOTIFICATION_TYPE, monitor.id, target_days).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Verifies server certificates like WebPKI and remembers the chain each
+/// host presented, so the chain of a check's own connection can be
+/// inspected afterwards. With `accept_invalid` every certificate is accepted
+/// and only the verification error is recorded.
+pub struct RecordingVerifier {
+    inner: WebPkiVerifier,
+    accept_invalid: bool,
+    chains: Mutex<HashMap<String, PeerChain>>,
+}
+
+/// Certificates presented by a host, leaf first, and why they did not verify.
+#[derive(Debug, Clone)]
+struct PeerChain {
+    certificates: Vec<Certificate>,
+    error: Option<String>,
+}
+
+impl RecordingVerifier {
+    pub fn new(roots: RootCertStore, accept_invalid: bool) -> Self {
+        Self {
+            inner: WebPkiVerifier::new(roots, None),
+            accept_invalid,
+            chains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Chain last presented by `host`, if a TLS connection was made to it.
+    pub fn tls_info(&self, host: &str) -> Option<Result<TlsInfo, String>> {
+        let chain = self.chains.lock().unwrap().get(&host.to_ascii_lowercase()).cloned()?;
+
+        let count = chain.certificates.len();
+        let certificates = chain
+            .certificates
+            .iter()
+            .enumerate()
+            .map(|(index, cert)| parse_certificate(&cert.0, index, count))
+            .collect::<Result<Vec<_>, _>>();
+        Some(certificates.map(|certificates| TlsInfo {
+            valid: chain.error.is_none(),
+            error: chain.error,
+            chain: certificates,
+        }))
+    }
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        server_name: &ServerName,
+        scts: &mut dyn Iterator<Item = &[u8]>,
+        ocsp_response: &[u8],
+        now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let result = self
+            .inner
+            .verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now);
+
+        let host = match server_name {
+            ServerName::DnsName(name) => Some(name.as_ref().to_ascii_lowercase()),
+            ServerName::IpAddress(ip) => Some(ip.to_string()),
+            _ => None,
+        };
+        if let Some(host) = host {
+            let chain = PeerChain {
+                certificates: std::iter::once(end_entity).chain(intermediates).cloned().collect(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+            };
+            self.chains.lock().unwrap().insert(host, chain);
+        }
+
+        match result {
+            Err(_) if self.accept_invalid => Ok(ServerCertVerified::assertion()),
+            result => result,
+        }
+    }
+}
+
+fn parse_certificate(der: &[u8], index: usize, count: usize) -> Result<CertificateInfo, String> {
+    let (_, cert) = X509Certificate::from_der(der).map_err(|e| e.to_string())?;
+
+    let subject = cert.subject().to_string();
+    let issuer = cert.issuer().to_string();
+    let cert_type = if index == 0 {
+        "server"
+    } else if index == count - 1 && subject == issuer {
+        "root CA"
+    } else {
+        "intermediate CA"
+    };
+
+    let common_name = cert
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(str::to_string);
+
+    let subject_alt_names = match cert.subject_alternative_name() {
+        Ok(Some(san)) => san
+            .value
+            .general_names
+            .iter()
+            .filter_map(|name| match name {
+                GeneralName::DNSName(dns) => Some(dns.to_string()),
+                GeneralName::IPAddress(ip) => match ip.len() {
+                    4 => Some(std::net::Ipv4Addr::from(<[u8; 4]>::try_from(*ip).ok()?).to_string()),
+                    16 => Some(std::net::Ipv6Addr::from(<[u8; 16]>::try_from(*ip).ok()?).to_string()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let timestamp = |time: ASN1Time| -> Result<DateTime<Utc>, String> {
+        Utc.timestamp_opt(time.timestamp(), 0)
+            .single()
+            .ok_or_else(|| "Invalid certificate validity".to_string())
+    };
+    let valid_from = timestamp(cert.validity().not_before)?;
+    let valid_to = timestamp(cert.validity().not_after)?;
+
+    Ok(CertificateInfo {
+        cert_type: cert_type.to_string(),
+        subject,
+        common_name,
+        issuer,
+        subject_alt_names,
+        serial: cert.raw_serial_as_string(),
+        valid_from,
+        valid_to,
+        days_remaining: (valid_to - Utc::now()).num_days(),
+    })
+}
@@ -0,0 +1,95 @@
+use axum::{
+    extract::{State, Path},
+    routing::{get, post, put, delete},
+    Router,
+    Json,
+};
+use std::sync::Arc;
+use crate::{
+    models::docker_host::{CreateDockerHost, UpdateDockerHost},
+    services::docker_host::DockerHostService,
+    error::AppError,
+    middleware::auth::Claims,
+};
+
+pub fn docker_host_routes() -> Router {
+    Router::new()
+        .route("/", get(list_docker_hosts))
+        .route("/", post(create_docker_host))
+        .route("/:id", get(get_docker_host))
+        .route("/:id", put(update_docker_host))
+        .route("/:id", delete(delete_docker_host))
+        .route("/:id/test", post(test_docker_host))
+}
+
+async fn list_docker_hosts(
+    State(docker_host_service): State<Arc<DockerHostService>>,
+    claims: Claims,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let docker_hosts = docker_host_service.list(claims.sub).await?;
+    Ok(Json(serde_json::json!({
+        "docker_hosts": docker_hosts
+    })))
+}
+
+async fn create_docker_host(
+    State(docker_host_service): State<Arc<DockerHostService>>,
+    claims: Claims,
+    Json(docker_host): Json<CreateDockerHost>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let docker_host = docker_host_service.create(claims.sub, docker_host).await?;
+    Ok(Json(serde_json::json!({
+        "message": "Docker host created successfully",
+        "docker_host": docker_host
+    })))
+}
+
+async fn get_docker_host(
+    State(docker_host_service): State<Arc<DockerHostService>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let docker_host = docker_host_service.get(id, claims.sub).await?;
+    Ok(Json(serde_json::json!({
+        "docker_host": docker_host
+    })))
+}
+
+async fn update_docker_host(
+    State(docker_host_service): State<Arc<DockerHostService>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+    Json(docker_host): Json<UpdateDockerHost>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let docker_host = docker_host_service.update(id, claims.sub, docker_host).await?;
+    Ok(Json(serde_json::json!({
+        "message": "Docker host updated successfully",
+        "docker_host": docker_host
+    })))
+}
+
+async fn delete_docker_host(
+    State(docker_host_service): State<Arc<DockerHostService>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let deleted = docker_host_service.delete(id, claims.sub).await?;
+    if deleted {
+        Ok(Json(serde_json::json!({
+            "message": "Docker host deleted successfully"
+        })))
+    } else {
+        Err(AppError::NotFound)
+    }
+}
+
+async fn test_docker_host(
+    State(docker_host_service): State<Arc<DockerHostService>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let count = docker_host_service.test(id, claims.sub).await?;
+    Ok(Json(serde_json::json!({
+        "message": format!("Connected Successfully. Amount of containers: {}", count)
+    })))
+}
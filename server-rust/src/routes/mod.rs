@@ -2,3 +2,6 @@
 pub mod health;
 pub mod auth;
 pub mod monitor;
+pub mod push;
+pub mod docker_host;
+pub mod proxy;
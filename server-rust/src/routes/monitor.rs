@@ -1,13 +1,14 @@
 use axum::{
-    extract::{State, Path},
+    extract::{State, Path, Query},
     routing::{get, post, put, delete},
     Router,
     Json,
 };
+use serde::Deserialize;
 use std::sync::Arc;
 use crate::{
     models::monitor::{CreateMonitor, UpdateMonitor},
-    services::monitor::MonitorService,
+    services::{monitor::MonitorService, scheduler::Scheduler},
     error::AppError,
     middleware::auth::Claims,
 };
@@ -20,13 +21,27 @@ pub fn monitor_routes() -> Router {
         .route("/:id", put(update_monitor))
         .route("/:id", delete(delete_monitor))
         .route("/:id/check", post(check_monitor))
+        .route("/:id/pause", post(pause_monitor))
+        .route("/:id/resume", post(resume_monitor))
+}
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    /// Nest monitors under their groups.
+    #[serde(default)]
+    tree: bool,
 }
 
 async fn list_monitors(
     State(monitor_service): State<Arc<MonitorService>>,
     claims: Claims,
+    Query(query): Query<ListQuery>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let monitors = monitor_service.list(claims.sub).await?;
+    let monitors = if query.tree {
+        serde_json::json!(monitor_service.list_tree(claims.sub).await?)
+    } else {
+        serde_json::json!(monitor_service.list(claims.sub).await?)
+    };
     Ok(Json(serde_json::json!({
         "monitors": monitors
     })))
@@ -34,10 +49,12 @@ async fn list_monitors(
 
 async fn create_monitor(
     State(monitor_service): State<Arc<MonitorService>>,
+    State(scheduler): State<Arc<Scheduler>>,
     claims: Claims,
     Json(monitor): Json<CreateMonitor>,
 ) -> Result<Json<serde_json::Value>, AppError> {
     let monitor = monitor_service.create(claims.sub, monitor).await?;
+    scheduler.schedule(&monitor);
     Ok(Json(serde_json::json!({
         "message": "Monitor created successfully",
         "monitor": monitor
@@ -50,18 +67,22 @@ async fn get_monitor(
     Path(id): Path<i64>,
 ) -> Result<Json<serde_json::Value>, AppError> {
     let monitor = monitor_service.get(id, claims.sub).await?;
+    let tls_info = monitor_service.tls_info(id, claims.sub).await?;
     Ok(Json(serde_json::json!({
-        "monitor": monitor
+        "monitor": monitor,
+        "tls_info": tls_info
     })))
 }
 
 async fn update_monitor(
     State(monitor_service): State<Arc<MonitorService>>,
+    State(scheduler): State<Arc<Scheduler>>,
     claims: Claims,
     Path(id): Path<i64>,
     Json(monitor): Json<UpdateMonitor>,
 ) -> Result<Json<serde_json::Value>, AppError> {
     let monitor = monitor_service.update(id, claims.sub, monitor).await?;
+    scheduler.schedule(&monitor);
     Ok(Json(serde_json::json!({
         "message": "Monitor updated successfully",
         "monitor": monitor
@@ -70,17 +91,47 @@ async fn update_monitor(
 
 async fn delete_monitor(
     State(monitor_service): State<Arc<MonitorService>>,
+    State(scheduler): State<Arc<Scheduler>>,
     claims: Claims,
     Path(id): Path<i64>,
 ) -> Result<Json<serde_json::Value>, AppError> {
     let deleted = monitor_service.delete(id, claims.sub).await?;
-    if deleted {
-        Ok(Json(serde_json::json!({
-            "message": "Monitor deleted successfully"
-        })))
-    } else {
-        Err(AppError::NotFound)
+    for id in deleted {
+        scheduler.unschedule(id);
     }
+    Ok(Json(serde_json::json!({
+        "message": "Monitor deleted successfully"
+    })))
+}
+
+async fn pause_monitor(
+    State(monitor_service): State<Arc<MonitorService>>,
+    State(scheduler): State<Arc<Scheduler>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let monitors = monitor_service.set_active(id, claims.sub, false).await?;
+    for monitor in &monitors {
+        scheduler.unschedule(monitor.id);
+    }
+    Ok(Json(serde_json::json!({
+        "message": "Monitor paused successfully"
+    })))
+}
+
+async fn resume_monitor(
+    State(monitor_service): State<Arc<MonitorService>>,
+    State(scheduler): State<Arc<Scheduler>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let monitors = monitor_service.set_active(id, claims.sub, true).await?;
+    for monitor in &monitors {
+        scheduler.schedule(monitor);
+    }
+    Ok(Json(serde_json::json!({
+        "message": "Monitor resumed successfully"
+    })))
 }
 
 async fn check_monitor(
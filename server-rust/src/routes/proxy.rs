@@ -0,0 +1,83 @@
+use axum::{
+    extract::{State, Path},
+    routing::{get, post, put, delete},
+    Router,
+    Json,
+};
+use std::sync::Arc;
+use crate::{
+    models::proxy::{CreateProxy, UpdateProxy},
+    services::proxy::ProxyService,
+    error::AppError,
+    middleware::auth::Claims,
+};
+
+pub fn proxy_routes() -> Router {
+    Router::new()
+        .route("/", get(list_proxies))
+        .route("/", post(create_proxy))
+        .route("/:id", get(get_proxy))
+        .route("/:id", put(update_proxy))
+        .route("/:id", delete(delete_proxy))
+}
+
+async fn list_proxies(
+    State(proxy_service): State<Arc<ProxyService>>,
+    claims: Claims,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let proxies = proxy_service.list(claims.sub).await?;
+    Ok(Json(serde_json::json!({
+        "proxies": proxies
+    })))
+}
+
+async fn create_proxy(
+    State(proxy_service): State<Arc<ProxyService>>,
+    claims: Claims,
+    Json(proxy): Json<CreateProxy>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let proxy = proxy_service.create(claims.sub, proxy).await?;
+    Ok(Json(serde_json::json!({
+        "message": "Proxy created successfully",
+        "proxy": proxy
+    })))
+}
+
+async fn get_proxy(
+    State(proxy_service): State<Arc<ProxyService>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let proxy = proxy_service.get(id, claims.sub).await?;
+    Ok(Json(serde_json::json!({
+        "proxy": proxy
+    })))
+}
+
+async fn update_proxy(
+    State(proxy_service): State<Arc<ProxyService>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+    Json(proxy): Json<UpdateProxy>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let proxy = proxy_service.update(id, claims.sub, proxy).await?;
+    Ok(Json(serde_json::json!({
+        "message": "Proxy updated successfully",
+        "proxy": proxy
+    })))
+}
+
+async fn delete_proxy(
+    State(proxy_service): State<Arc<ProxyService>>,
+    claims: Claims,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let deleted = proxy_service.delete(id, claims.sub).await?;
+    if deleted {
+        Ok(Json(serde_json::json!({
+            "message": "Proxy deleted successfully"
+        })))
+    } else {
+        Err(AppError::NotFound)
+    }
+}
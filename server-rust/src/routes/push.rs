@@ -0,0 +1,38 @@
+use axum::{
+    extract::{State, Path, Query},
+    routing::get,
+    Router,
+    Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use crate::{
+    services::monitor::MonitorService,
+    error::AppError,
+};
+
+pub fn push_routes() -> Router {
+    Router::new()
+        .route("/:token", get(push).post(push))
+}
+
+#[derive(Debug, Deserialize)]
+struct PushQuery {
+    status: Option<String>,
+    msg: Option<String>,
+    ping: Option<f64>,
+}
+
+async fn push(
+    State(monitor_service): State<Arc<MonitorService>>,
+    Path(token): Path<String>,
+    Query(query): Query<PushQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let ping = query.ping.map(|ping| ping.round() as i32);
+    monitor_service
+        .push(&token, query.status.as_deref(), query.msg, ping)
+        .await?;
+    Ok(Json(serde_json::json!({
+        "ok": true
+    })))
+}
@@ -0,0 +1,90 @@
+use sqlx::SqlitePool;
+use crate::{
+    models::docker_host::{
+        DockerHost, CreateDockerHost, UpdateDockerHost, DOCKER_TYPE_SOCKET, DOCKER_TYPE_TCP,
+    },
+    monitor_types::docker::{connect, count_containers},
+    error::AppError,
+};
+use std::time::Duration;
+
+pub struct DockerHostService {
+    pool: SqlitePool,
+}
+
+impl DockerHostService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, user_id: i64, host: CreateDockerHost) -> Result<DockerHost, AppError> {
+        validate(&host.name, &host.docker_type, &host.docker_daemon)?;
+        let host = DockerHost::create(&self.pool, user_id, host).await?;
+        Ok(host)
+    }
+
+    pub async fn get(&self, id: i64, user_id: i64) -> Result<DockerHost, AppError> {
+        let host = DockerHost::find_by_id(&self.pool, id, user_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        Ok(host)
+    }
+
+    pub async fn list(&self, user_id: i64) -> Result<Vec<DockerHost>, AppError> {
+        let hosts = DockerHost::list_by_user(&self.pool, user_id).await?;
+        Ok(hosts)
+    }
+
+    pub async fn update(
+        &self,
+        id: i64,
+        user_id: i64,
+        host: UpdateDockerHost,
+    ) -> Result<DockerHost, AppError> {
+        let existing = self.get(id, user_id).await?;
+        validate(
+            host.name.as_deref().unwrap_or(&existing.name),
+            host.docker_type.as_deref().unwrap_or(&existing.docker_type),
+            host.docker_daemon.as_deref().unwrap_or(&existing.docker_daemon),
+        )?;
+
+        let host = DockerHost::update(&self.pool, id, user_id, host)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        Ok(host)
+    }
+
+    /// Deletes the host, unless monitors still check containers on it.
+    pub async fn delete(&self, id: i64, user_id: i64) -> Result<bool, AppError> {
+        let monitors = DockerHost::monitor_count(&self.pool, id, user_id).await?;
+        if monitors > 0 {
+            return Err(AppError::BadRequest(format!(
+                "Docker host is used by {} monitor(s), move or delete them first",
+                monitors
+            )));
+        }
+        let deleted = DockerHost::delete(&self.pool, id, user_id).await?;
+        Ok(deleted)
+    }
+
+    /// Connects to the host and returns how many containers it has.
+    pub async fn test(&self, id: i64, user_id: i64) -> Result<usize, AppError> {
+        let host = self.get(id, user_id).await?;
+        let docker = connect(&host, Duration::from_secs(10)).map_err(AppError::BadRequest)?;
+        let count = count_containers(&docker).await.map_err(AppError::BadRequest)?;
+        Ok(count)
+    }
+}
+
+fn validate(name: &str, docker_type: &str, docker_daemon: &str) -> Result<(), AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::BadRequest("Name is required".to_string()));
+    }
+    if docker_type != DOCKER_TYPE_SOCKET && docker_type != DOCKER_TYPE_TCP {
+        return Err(AppError::BadRequest(format!("Unsupported docker type: {}", docker_type)));
+    }
+    if docker_daemon.trim().is_empty() {
+        return Err(AppError::BadRequest("Docker daemon is required".to_string()));
+    }
+    Ok(())
+}
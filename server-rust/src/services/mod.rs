@@ -1,5 +1,8 @@
 // Services module
 pub mod auth;
 pub mod monitor;
+pub mod scheduler;
 pub mod notification;
+pub mod docker_host;
+pub mod proxy;
 pub mod status_page;
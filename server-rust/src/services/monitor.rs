@@ -1,26 +1,112 @@
 use sqlx::SqlitePool;
 use crate::{
-    models::monitor::{Monitor, CreateMonitor, UpdateMonitor},
+    config::secret,
+    models::{
+        docker_host::DockerHost,
+        monitor::{Monitor, MonitorNode, CreateMonitor, UpdateMonitor, STATUS_UP, STATUS_DOWN, STATUS_PENDING},
+        proxy::Proxy,
+        tls_info::TlsInfo,
+    },
+    monitor_conditions::evaluator,
+    monitor_types::{
+        CheckResult,
+        MonitorTypeRegistry,
+        database::{Database, DatabaseMonitorType},
+        dns::DnsMonitorType,
+        docker::{self, DockerMonitorType},
+        group::GroupMonitorType,
+        grpc::GrpcMonitorType,
+        http::{HttpClients, HttpMonitorType, validate_client_tls},
+        json_query::JsonQueryMonitorType,
+        keyword::KeywordMonitorType,
+        mongodb::MongoDbMonitorType,
+        mqtt::MqttMonitorType,
+        ping::PingMonitorType,
+        port::PortMonitorType,
+        push::PushMonitorType,
+        rabbitmq::RabbitMqMonitorType,
+        radius::RadiusMonitorType,
+        redis::RedisMonitorType,
+        snmp::SnmpMonitorType,
+        steam::SteamMonitorType,
+    },
+    services::notification::NotificationService,
     error::AppError,
 };
-use reqwest::Client;
-use std::time::Duration;
+use serde_json::Value;
+use std::sync::Arc;
 
 pub struct MonitorService {
     pool: SqlitePool,
-    http_client: Client,
+    monitor_types: MonitorTypeRegistry,
+    http_clients: Arc<HttpClients>,
 }
 
 impl MonitorService {
-    pub fn new(pool: SqlitePool) -> Self {
-        let http_client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .unwrap();
-        Self { pool, http_client }
+    pub fn new(
+        pool: SqlitePool,
+        notification_service: Arc<NotificationService>,
+        http_clients: Arc<HttpClients>,
+    ) -> Self {
+
+        let mut monitor_types = MonitorTypeRegistry::new();
+        monitor_types.register(HttpMonitorType::new("http", http_clients.clone()));
+        monitor_types.register(HttpMonitorType::new("https", http_clients.clone()));
+        monitor_types.register(KeywordMonitorType::new(http_clients.clone()));
+        monitor_types.register(JsonQueryMonitorType::new(http_clients.clone()));
+        monitor_types.register(PingMonitorType);
+        monitor_types.register(PortMonitorType);
+        monitor_types.register(DnsMonitorType::new(pool.clone(), notification_service));
+        monitor_types.register(MqttMonitorType);
+        monitor_types.register(PushMonitorType);
+        monitor_types.register(GroupMonitorType::new(pool.clone()));
+        monitor_types.register(DatabaseMonitorType::new(Database::Postgres));
+        monitor_types.register(DatabaseMonitorType::new(Database::MySql));
+        monitor_types.register(DatabaseMonitorType::new(Database::SqlServer));
+        monitor_types.register(RedisMonitorType);
+        monitor_types.register(GrpcMonitorType);
+        monitor_types.register(DockerMonitorType::new(pool.clone()));
+        monitor_types.register(RadiusMonitorType);
+        monitor_types.register(SteamMonitorType::new("steam"));
+        monitor_types.register(SteamMonitorType::new("gamedig"));
+        #[cfg(feature = "kafka")]
+        monitor_types.register(crate::monitor_types::kafka::KafkaProducerMonitorType);
+        monitor_types.register(MongoDbMonitorType);
+        monitor_types.register(RabbitMqMonitorType::default());
+        monitor_types.register(SnmpMonitorType);
+
+        Self { pool, monitor_types, http_clients }
     }
 
-    pub async fn create(&self, user_id: i64, monitor: CreateMonitor) -> Result<Monitor, AppError> {
+    pub async fn create(&self, user_id: i64, mut monitor: CreateMonitor) -> Result<Monitor, AppError> {
+        if monitor.type_ == "push" && monitor.push_token.is_none() {
+            monitor.push_token = Some(generate_push_token());
+        }
+
+        let config = monitor.config.clone().unwrap_or_else(|| Value::Object(Default::default()));
+        let monitor_type = self.monitor_types.require(&monitor.type_)?;
+        monitor_type.validate(&monitor.url, &config)?;
+        if let Some(conditions) = &monitor.conditions {
+            evaluator::validate(conditions, monitor_type.condition_variables()).map_err(AppError::BadRequest)?;
+        }
+        if let Some(parent) = monitor.parent {
+            self.validate_parent(None, parent, user_id).await?;
+        }
+        if monitor.type_ == "docker" {
+            self.validate_docker_host(&config, user_id).await?;
+        }
+        validate_client_tls(monitor.tls_cert.as_deref(), monitor.tls_key.as_deref(), monitor.tls_ca.as_deref())?;
+        monitor.tls_key = monitor
+            .tls_key
+            .as_deref()
+            .map(secret::encrypt)
+            .transpose()
+            .map_err(AppError::BadRequest)?;
+        match monitor.proxy_id {
+            Some(proxy_id) => self.validate_proxy(proxy_id, user_id).await?,
+            None => monitor.proxy_id = Proxy::find_default(&self.pool, user_id).await?.map(|proxy| proxy.id),
+        }
+
         let monitor = Monitor::create(&self.pool, user_id, monitor).await?;
         Ok(monitor)
     }
@@ -32,90 +118,322 @@ impl MonitorService {
         Ok(monitor)
     }
 
+    /// Returns the certificate chain last seen by an HTTPS monitor.
+    pub async fn tls_info(&self, id: i64, user_id: i64) -> Result<Option<TlsInfo>, AppError> {
+        let monitor = self.get(id, user_id).await?;
+        let info = TlsInfo::find_by_monitor(&self.pool, monitor.id).await?;
+        Ok(info)
+    }
+
     pub async fn list(&self, user_id: i64) -> Result<Vec<Monitor>, AppError> {
         let monitors = Monitor::list_by_user(&self.pool, user_id).await?;
         Ok(monitors)
     }
 
+    pub async fn list_tree(&self, user_id: i64) -> Result<Vec<MonitorNode>, AppError> {
+        let monitors = Monitor::list_tree_by_user(&self.pool, user_id).await?;
+        Ok(monitors)
+    }
+
     pub async fn update(
         &self,
         id: i64,
         user_id: i64,
-        monitor: UpdateMonitor,
+        mut monitor: UpdateMonitor,
     ) -> Result<Monitor, AppError> {
+        // Validate the monitor as it will look after the update
+        let existing = self.get(id, user_id).await?;
+        let type_ = monitor.type_.as_deref().unwrap_or(&existing.type_);
+        if type_ == "push" && monitor.push_token.is_none() && existing.push_token.is_none() {
+            monitor.push_token = Some(generate_push_token());
+        }
+        let url = monitor.url.as_deref().unwrap_or(&existing.url);
+        let config = monitor.config.as_ref().unwrap_or(&existing.config.0);
+        let conditions = monitor.conditions.as_ref().unwrap_or(&existing.conditions.0);
+        let monitor_type = self.monitor_types.require(type_)?;
+        monitor_type.validate(url, config)?;
+        evaluator::validate(conditions, monitor_type.condition_variables()).map_err(AppError::BadRequest)?;
+        if let Some(Some(parent)) = monitor.parent {
+            self.validate_parent(Some(id), parent, user_id).await?;
+        }
+        if type_ == "docker" {
+            self.validate_docker_host(config, user_id).await?;
+        }
+        if let Some(Some(proxy_id)) = monitor.proxy_id {
+            self.validate_proxy(proxy_id, user_id).await?;
+        }
+        let existing_key = existing
+            .tls_key
+            .as_deref()
+            .map(secret::decrypt)
+            .transpose()
+            .map_err(|e| AppError::BadRequest(format!("Cannot read the stored client key: {}", e)))?;
+        validate_client_tls(
+            monitor.tls_cert.as_ref().unwrap_or(&existing.tls_cert).as_deref(),
+            monitor.tls_key.as_ref().unwrap_or(&existing_key).as_deref(),
+            monitor.tls_ca.as_ref().unwrap_or(&existing.tls_ca).as_deref(),
+        )?;
+        if let Some(Some(key)) = &mut monitor.tls_key {
+            *key = secret::encrypt(key).map_err(AppError::BadRequest)?;
+        }
+
         let monitor = Monitor::update(&self.pool, id, user_id, monitor)
             .await?
             .ok_or(AppError::NotFound)?;
+        self.http_clients.remove(id);
         Ok(monitor)
     }
 
-    pub async fn delete(&self, id: i64, user_id: i64) -> Result<bool, AppError> {
+    /// Deletes the monitor and any monitors nested under it, returning
+    /// the ids of everything deleted.
+    pub async fn delete(&self, id: i64, user_id: i64) -> Result<Vec<i64>, AppError> {
         let deleted = Monitor::delete(&self.pool, id, user_id).await?;
+        if deleted.is_empty() {
+            return Err(AppError::NotFound);
+        }
+        for &id in &deleted {
+            self.http_clients.remove(id);
+        }
         Ok(deleted)
     }
 
+    /// Pauses or resumes the monitor and any monitors nested under it.
+    pub async fn set_active(&self, id: i64, user_id: i64, active: bool) -> Result<Vec<Monitor>, AppError> {
+        let monitors = Monitor::set_active(&self.pool, id, user_id, active).await?;
+        if monitors.is_empty() {
+            return Err(AppError::NotFound);
+        }
+        Ok(monitors)
+    }
+
+    async fn validate_proxy(&self, proxy_id: i64, user_id: i64) -> Result<(), AppError> {
+        Proxy::find_by_id(&self.pool, proxy_id, user_id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest(format!("Proxy not found: {}", proxy_id)))?;
+        Ok(())
+    }
+
+    async fn validate_docker_host(&self, config: &Value, user_id: i64) -> Result<(), AppError> {
+        let docker_host = docker::docker_host_id(config)?;
+        DockerHost::find_by_id(&self.pool, docker_host, user_id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest(format!("Docker host not found: {}", docker_host)))?;
+        Ok(())
+    }
+
+    /// Checks that `parent` is a group of the user that does not contain
+    /// the monitor being saved.
+    async fn validate_parent(&self, id: Option<i64>, parent: i64, user_id: i64) -> Result<(), AppError> {
+        let group = Monitor::find_by_id(&self.pool, parent, user_id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest(format!("Parent monitor not found: {}", parent)))?;
+        if group.type_ != "group" {
+            return Err(AppError::BadRequest("Parent monitor must be a group".to_string()));
+        }
+
+        if let Some(id) = id {
+            if parent == id || Monitor::descendant_ids(&self.pool, id).await?.contains(&parent) {
+                return Err(AppError::BadRequest("A group cannot be nested inside itself".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn check_status(&self, id: i64, user_id: i64) -> Result<(), AppError> {
         let monitor = self.get(id, user_id).await?;
+        self.check(&monitor).await?;
+        Ok(())
+    }
+
+    /// Runs a single check for the monitor, records the result and returns
+    /// the number of seconds until the next check.
+    ///
+    /// A successful check still fails when the monitor's conditions do not
+    /// hold for the variables it reported.
+    pub async fn check(&self, monitor: &Monitor) -> Result<i32, AppError> {
+        let monitor_type = self.monitor_types.require(&monitor.type_)?;
+
         let start_time = std::time::Instant::now();
+        let mut result = monitor_type.check(monitor).await;
+        if result.skipped {
+            return Ok(next_interval(monitor, &monitor.status, result.next_check));
+        }
+        if result.is_up() {
+            if let Err(message) = evaluator::evaluate(&monitor.conditions, &result.variables) {
+                result.status = STATUS_DOWN;
+                result.message = Some(message);
+            }
+        }
 
-        let result = match monitor.type_.as_str() {
-            "http" | "https" => self.check_http(&monitor).await,
-            "ping" => self.check_ping(&monitor).await,
-            _ => Err(AppError::BadRequest("Unsupported monitor type".to_string())),
-        };
+        let ping = result
+            .latency
+            .unwrap_or_else(|| start_time.elapsed().as_millis() as i32);
+        let status = self.record(monitor, &result, ping).await?;
+        Ok(next_interval(monitor, status, result.next_check))
+    }
 
-        let ping = start_time.elapsed().as_millis() as i32;
-        let (status, message) = match result {
-            Ok(_) => ("up", None),
-            Err(e) => ("down", Some(e.to_string())),
-        };
+    /// Records a heartbeat sent to a push monitor's token.
+    pub async fn push(
+        &self,
+        token: &str,
+        status: Option<&str>,
+        message: Option<String>,
+        ping: Option<i32>,
+    ) -> Result<(), AppError> {
+        let monitor = Monitor::find_by_push_token(&self.pool, token)
+            .await?
+            .filter(|monitor| monitor.type_ == "push" && monitor.active)
+            .ok_or(AppError::NotFound)?;
 
-        Monitor::update_status(&self.pool, id, status, Some(ping), message.as_deref()).await?;
+        let result = push_result(status, message);
+        self.record(&monitor, &result, ping.unwrap_or_default()).await?;
         Ok(())
     }
 
-    async fn check_http(&self, monitor: &Monitor) -> Result<(), AppError> {
-        let response = self
-            .http_client
-            .get(&monitor.url)
-            .timeout(Duration::from_secs(monitor.timeout as u64))
-            .send()
-            .await?;
+    /// Stores a check result and returns the monitor's new status.
+    ///
+    /// A failed check only marks the monitor down once `max_retries`
+    /// consecutive failures have been seen; until then it is pending.
+    async fn record(&self, monitor: &Monitor, result: &CheckResult, ping: i32) -> Result<&'static str, AppError> {
+        let (status, retries) = next_status(result.status, monitor.retries, monitor.max_retries);
+
+        Monitor::update_status(
+            &self.pool,
+            monitor.id,
+            status,
+            retries,
+            Some(ping),
+            result.message.as_deref(),
+            result.details.as_ref(),
+        )
+        .await?;
+        Ok(status)
+    }
+}
+
+/// Status and retry count after a check with the given result status.
+///
+/// Failures count as retries and leave the monitor pending until
+/// `max_retries` of them were seen in a row. A pending result, e.g. a
+/// container that is still starting, keeps the count as it is.
+fn next_status(result: &str, retries: i32, max_retries: i32) -> (&'static str, i32) {
+    match result {
+        STATUS_UP => (STATUS_UP, 0),
+        STATUS_PENDING => (STATUS_PENDING, retries),
+        _ if max_retries > 0 && retries < max_retries => (STATUS_PENDING, retries + 1),
+        // Keep counting retries while down
+        _ => (STATUS_DOWN, retries + 1),
+    }
+}
+
+/// Seconds until a monitor with the given status is checked again.
+///
+/// A delay asked for by the check wins, then `retry_interval` while pending.
+fn next_interval(monitor: &Monitor, status: &str, next_check: Option<i32>) -> i32 {
+    match next_check {
+        Some(seconds) => seconds,
+        None if status == STATUS_PENDING && monitor.retry_interval > 0 => monitor.retry_interval,
+        None => monitor.interval,
+    }
+}
+
+/// Result for a push; any status other than "up" marks the monitor down.
+fn push_result(status: Option<&str>, message: Option<String>) -> CheckResult {
+    let message = message.unwrap_or_else(|| "OK".to_string());
+    match status {
+        None | Some(STATUS_UP) => CheckResult::up().with_message(message),
+        Some(_) => CheckResult::down(message),
+    }
+}
+
+/// Random token identifying a push monitor in its push URL.
+fn generate_push_token() -> String {
+    use rand::{distributions::Alphanumeric, Rng};
+
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
 
-        if !response.status().is_success() {
-            return Err(AppError::BadRequest(format!(
-                "HTTP status code: {}",
-                response.status()
-            )));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_failure_is_pending() {
+        assert_eq!(next_status(STATUS_DOWN, 0, 3), (STATUS_PENDING, 1));
+    }
+
+    #[test]
+    fn down_after_max_retries() {
+        let mut retries = 0;
+        let mut statuses = Vec::new();
+        for _ in 0..3 {
+            let (status, next) = next_status(STATUS_DOWN, retries, 2);
+            statuses.push(status);
+            retries = next;
         }
+        assert_eq!(statuses, [STATUS_PENDING, STATUS_PENDING, STATUS_DOWN]);
+        assert_eq!(retries, 3);
+    }
 
-        Ok(())
+    #[test]
+    fn stays_down() {
+        assert_eq!(next_status(STATUS_DOWN, 3, 2), (STATUS_DOWN, 4));
+        assert_eq!(next_status(STATUS_DOWN, 4, 2), (STATUS_DOWN, 5));
     }
 
-    async fn check_ping(&self, monitor: &Monitor) -> Result<(), AppError> {
-        // Extract hostname from URL
-        let hostname = monitor
-            .url
-            .replace("http://", "")
-            .replace("https://", "")
-            .split('/')
-            .next()
-            .ok_or_else(|| AppError::BadRequest("Invalid URL".to_string()))?;
+    #[test]
+    fn pending_result_keeps_retries() {
+        assert_eq!(next_status(STATUS_PENDING, 0, 3), (STATUS_PENDING, 0));
+        assert_eq!(next_status(STATUS_PENDING, 2, 3), (STATUS_PENDING, 2));
+    }
+
+    #[test]
+    fn no_retries_goes_straight_down() {
+        assert_eq!(next_status(STATUS_DOWN, 0, 0), (STATUS_DOWN, 1));
+    }
+
+    #[test]
+    fn up_resets_retries() {
+        assert_eq!(next_status(STATUS_UP, 2, 3), (STATUS_UP, 0));
+        assert_eq!(next_status(STATUS_UP, 5, 3), (STATUS_UP, 0));
+    }
 
-        // Use the system's ping command
-        let output = tokio::process::Command::new("ping")
-            .arg("-c")
-            .arg("1")
-            .arg("-W")
-            .arg(monitor.timeout.to_string())
-            .arg(hostname)
-            .output()
-            .await?;
+    #[test]
+    fn push_status_mapping() {
+        let result = push_result(None, None);
+        assert_eq!(result.status, STATUS_UP);
+        assert_eq!(result.message.as_deref(), Some("OK"));
 
-        if !output.status.success() {
-            return Err(AppError::BadRequest("Ping failed".to_string()));
+        let result = push_result(Some("up"), Some("all good".to_string()));
+        assert_eq!(result.status, STATUS_UP);
+        assert_eq!(result.message.as_deref(), Some("all good"));
+
+        for status in ["down", "pending", "UP", ""] {
+            let result = push_result(Some(status), Some("disk full".to_string()));
+            assert_eq!(result.status, STATUS_DOWN, "status {:?}", status);
+            assert_eq!(result.message.as_deref(), Some("disk full"));
         }
+    }
 
-        Ok(())
+    #[test]
+    fn next_interval_prefers_check_delay() {
+        let monitor = Monitor {
+            interval: 60,
+            retry_interval: 20,
+            ..Monitor::for_test(1, "push")
+        };
+        assert_eq!(next_interval(&monitor, STATUS_UP, Some(15)), 15);
+        assert_eq!(next_interval(&monitor, STATUS_PENDING, Some(15)), 15);
+        assert_eq!(next_interval(&monitor, STATUS_PENDING, None), 20);
+        assert_eq!(next_interval(&monitor, STATUS_UP, None), 60);
+        assert_eq!(next_interval(&monitor, STATUS_DOWN, None), 60);
+
+        let no_retry_interval = Monitor { retry_interval: 0, ..monitor };
+        assert_eq!(next_interval(&no_retry_interval, STATUS_PENDING, None), 60);
     }
 }
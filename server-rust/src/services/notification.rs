@@ -0,0 +1,74 @@
+use sqlx::SqlitePool;
+use crate::{
+    models::{monitor::Monitor, notification::Notification},
+    error::AppError,
+};
+use reqwest::Client;
+use std::time::Duration;
+
+pub struct NotificationService {
+    pool: SqlitePool,
+    http_client: Client,
+}
+
+impl NotificationService {
+    pub fn new(pool: SqlitePool) -> Self {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap();
+        Self { pool, http_client }
+    }
+
+    /// Sends a message to every notification of the monitor's owner.
+    ///
+    /// Returns whether at least one notification was delivered.
+    pub async fn send(&self, monitor: &Monitor, message: &str) -> Result<bool, AppError> {
+        let notifications = Notification::list_by_user(&self.pool, monitor.user_id).await?;
+
+        let mut sent = false;
+        for notification in &notifications {
+            match self.deliver(notification, monitor, message).await {
+                Ok(()) => sent = true,
+                Err(e) => tracing::error!(
+                    "Cannot send notification to {}: {}",
+                    notification.name,
+                    e
+                ),
+            }
+        }
+
+        Ok(sent)
+    }
+
+    async fn deliver(
+        &self,
+        notification: &Notification,
+        monitor: &Monitor,
+        message: &str,
+    ) -> Result<(), String> {
+        match notification.type_.as_str() {
+            "webhook" => {
+                let url = notification
+                    .config
+                    .get("url")
+                    .and_then(|url| url.as_str())
+                    .ok_or_else(|| "Webhook URL is not configured".to_string())?;
+
+                self.http_client
+                    .post(url)
+                    .json(&serde_json::json!({
+                        "msg": message,
+                        "monitor": monitor,
+                    }))
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|e| e.to_string())?;
+
+                Ok(())
+            }
+            other => Err(format!("Unsupported notification type: {}", other)),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use crate::{
+    models::proxy::{Proxy, CreateProxy, UpdateProxy, PROTOCOLS},
+    monitor_types::http::HttpClients,
+    error::AppError,
+};
+
+pub struct ProxyService {
+    pool: SqlitePool,
+    http_clients: Arc<HttpClients>,
+}
+
+impl ProxyService {
+    pub fn new(pool: SqlitePool, http_clients: Arc<HttpClients>) -> Self {
+        Self { pool, http_clients }
+    }
+
+    pub async fn create(&self, user_id: i64, proxy: CreateProxy) -> Result<Proxy, AppError> {
+        validate(&proxy.protocol, &proxy.host, proxy.port)?;
+        let apply_existing = proxy.apply_existing;
+
+        let proxy = Proxy::create(&self.pool, user_id, proxy).await?;
+        self.after_save(&proxy, apply_existing).await?;
+        Ok(proxy)
+    }
+
+    pub async fn get(&self, id: i64, user_id: i64) -> Result<Proxy, AppError> {
+        let proxy = Proxy::find_by_id(&self.pool, id, user_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        Ok(proxy)
+    }
+
+    pub async fn list(&self, user_id: i64) -> Result<Vec<Proxy>, AppError> {
+        let proxies = Proxy::list_by_user(&self.pool, user_id).await?;
+        Ok(proxies)
+    }
+
+    pub async fn update(&self, id: i64, user_id: i64, proxy: UpdateProxy) -> Result<Proxy, AppError> {
+        let existing = self.get(id, user_id).await?;
+        validate(
+            proxy.protocol.as_deref().unwrap_or(&existing.protocol),
+            proxy.host.as_deref().unwrap_or(&existing.host),
+            proxy.port.unwrap_or(existing.port),
+        )?;
+        let apply_existing = proxy.apply_existing;
+
+        let proxy = Proxy::update(&self.pool, id, user_id, proxy)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        self.http_clients.remove_proxy(id);
+        self.after_save(&proxy, apply_existing).await?;
+        Ok(proxy)
+    }
+
+    pub async fn delete(&self, id: i64, user_id: i64) -> Result<bool, AppError> {
+        let deleted = Proxy::delete(&self.pool, id, user_id).await?;
+        if deleted {
+            self.http_clients.remove_proxy(id);
+        }
+        Ok(deleted)
+    }
+
+    /// Keeps a single default proxy and, if asked, moves every monitor onto
+    /// the saved proxy, like `server/proxy.js` does.
+    async fn after_save(&self, proxy: &Proxy, apply_existing: bool) -> Result<(), AppError> {
+        if proxy.is_default {
+            Proxy::clear_default(&self.pool, proxy.user_id, proxy.id).await?;
+        }
+        if apply_existing {
+            Proxy::apply_to_all_monitors(&self.pool, proxy.id, proxy.user_id).await?;
+        }
+        Ok(())
+    }
+}
+
+fn validate(protocol: &str, host: &str, port: i32) -> Result<(), AppError> {
+    if !PROTOCOLS.contains(&protocol) {
+        return Err(AppError::BadRequest(format!("Unsupported proxy protocol: {}", protocol)));
+    }
+    if host.trim().is_empty() {
+        return Err(AppError::BadRequest("Proxy host is required".to_string()));
+    }
+    if !(1..=65535).contains(&port) {
+        return Err(AppError::BadRequest("Proxy port must be between 1 and 65535".to_string()));
+    }
+    Ok(())
+}
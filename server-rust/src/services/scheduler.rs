@@ -0,0 +1,102 @@
+use sqlx::SqlitePool;
+use crate::{
+    models::monitor::Monitor,
+    services::monitor::MonitorService,
+    error::AppError,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Runs every monitor's check in the background, once per `interval` seconds
+/// (or `retry_interval` seconds while the monitor is pending).
+///
+/// Each monitor gets its own task. The task reloads the monitor before every
+/// check so that edits are picked up, and exits once the monitor is gone or
+/// paused.
+pub struct Scheduler {
+    pool: SqlitePool,
+    monitor_service: Arc<MonitorService>,
+    tasks: Mutex<HashMap<i64, JoinHandle<()>>>,
+}
+
+impl Scheduler {
+    pub fn new(pool: SqlitePool, monitor_service: Arc<MonitorService>) -> Self {
+        Self {
+            pool,
+            monitor_service,
+            tasks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Schedules every monitor currently stored in the database.
+    pub async fn start(&self) -> Result<(), AppError> {
+        let monitors = Monitor::list_all(&self.pool).await?;
+        tracing::info!("Scheduling {} monitor(s)", monitors.len());
+
+        for monitor in monitors.iter().filter(|monitor| monitor.active) {
+            self.schedule(monitor);
+        }
+
+        Ok(())
+    }
+
+    /// Starts (or restarts) the check loop for a monitor.
+    ///
+    /// The first check runs immediately, like it does in the Node server.
+    pub fn schedule(&self, monitor: &Monitor) {
+        let id = monitor.id;
+        let pool = self.pool.clone();
+        let monitor_service = self.monitor_service.clone();
+
+        let handle = tokio::spawn(async move {
+            loop {
+                let monitor = match Monitor::find(&pool, id).await {
+                    Ok(Some(monitor)) if monitor.active => monitor,
+                    Ok(Some(_)) => {
+                        tracing::debug!("Monitor #{} is paused, stopping", id);
+                        break;
+                    }
+                    Ok(None) => {
+                        tracing::debug!("Monitor #{} no longer exists, stopping", id);
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load monitor #{}: {}", id, e);
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        continue;
+                    }
+                };
+
+                let interval = match monitor_service.check(&monitor).await {
+                    Ok(interval) => interval,
+                    Err(e) => {
+                        tracing::error!("Check for monitor #{} failed: {:?}", id, e);
+                        monitor.interval
+                    }
+                };
+
+                tokio::time::sleep(Duration::from_secs(interval.max(1) as u64)).await;
+            }
+        });
+
+        if let Some(previous) = self.tasks.lock().unwrap().insert(id, handle) {
+            previous.abort();
+        }
+    }
+
+    /// Stops the check loop for a monitor, if one is running.
+    pub fn unschedule(&self, id: i64) {
+        if let Some(handle) = self.tasks.lock().unwrap().remove(&id) {
+            handle.abort();
+        }
+    }
+
+    /// Stops every running check loop.
+    pub fn stop(&self) {
+        for (_, handle) in self.tasks.lock().unwrap().drain() {
+            handle.abort();
+        }
+    }
+}